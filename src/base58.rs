@@ -0,0 +1,80 @@
+use crate::error::Error;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encode bytes as Base58 with the Bitcoin alphabet, leading zero bytes become `1`
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+    // Little endian base58 digits
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in data[zeros..].iter() {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat(b'1')
+        .take(zeros)
+        .chain(digits.iter().rev().map(|d| ALPHABET[*d as usize]))
+        .map(|c| c as char)
+        .collect()
+}
+
+/// Decode a Base58 string with the Bitcoin alphabet
+pub fn decode(data: &str) -> Result<Vec<u8>, Error> {
+    let zeros = data.bytes().take_while(|c| *c == b'1').count();
+    // Little endian bytes
+    let mut bytes: Vec<u8> = Vec::new();
+    for (position, c) in data.bytes().enumerate().skip(zeros) {
+        let mut carry = ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or(Error::InvalidCharacter { position })? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+    Ok(std::iter::repeat(0)
+        .take(zeros)
+        .chain(bytes.iter().rev().copied())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn known_vectors() {
+        assert_eq!(encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+        assert_eq!(
+            decode("11233QC4").unwrap(),
+            vec![0, 0, 0x28, 0x7f, 0xb4, 0xcd]
+        );
+    }
+    #[test]
+    fn can_be_encoded_and_back() {
+        let data = (0..=255).rev().collect::<Vec<u8>>();
+        assert_eq!(decode(&encode(&data)).unwrap(), data);
+        assert_eq!(decode("").unwrap(), Vec::<u8>::new());
+    }
+    #[test]
+    fn rejects_invalid_characters() {
+        assert_eq!(
+            decode("11O").err(),
+            Some(Error::InvalidCharacter { position: 2 })
+        );
+    }
+}
@@ -0,0 +1,387 @@
+mod npy;
+mod npz;
+mod safetensors;
+use crate::dna::{Influence, LatentMode, DNA};
+use crate::error::Error;
+
+/// File format of a batch of latents
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TensorFormat {
+    /// NumPy `.npy`, holds only the latents
+    Npy,
+    /// NumPy `.npz`, an uncompressed zip of `latents.npy` and `ids.npy`
+    Npz,
+    /// Hugging Face safetensors with `latents` and `ids` tensors
+    Safetensors,
+}
+
+/// A batch of latents, one row per `DNA`, stored as a 2-D f32 tensor with an optional 1-D u64
+/// tensor of ids
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Batch {
+    pub latents: Vec<Vec<f32>>,
+    pub ids: Option<Vec<u64>>,
+}
+
+impl Batch {
+    /// Create a batch from DNA with the same layout, each exported with `mode`. DNA made for
+    /// different models are refused, the same way `DNA::merge` refuses them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Batch, LatentMode, DNA};
+    ///
+    /// let population = (0..8).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    ///
+    /// let batch = Batch::from_dna(&population, LatentMode::Included).unwrap();
+    ///
+    /// assert_eq!(batch.latents.len(), 8);
+    /// ```
+    pub fn from_dna(population: &[DNA], mode: LatentMode) -> Result<Batch, Error> {
+        if let Some(first) = population.first() {
+            if population
+                .iter()
+                .any(|d| d.get_layout() != first.get_layout())
+            {
+                return Err(Error::InvalidSetting("DNA in a batch need the same layout"));
+            }
+            let mut models = population.iter().filter_map(|d| d.model.as_ref());
+            if let Some(model) = models.next() {
+                if models.any(|m| m != model) {
+                    return Err(Error::ModelMismatch);
+                }
+            }
+        }
+        Ok(Batch {
+            latents: population
+                .iter()
+                .map(|d| d.to_latent_vec_with(mode))
+                .collect(),
+            ids: None,
+        })
+    }
+    /// Convert every row back to a `DNA` split into genes of `gene_size` markers
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Batch, Influence, LatentMode, DNA};
+    ///
+    /// let population = (0..8).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    /// let batch = Batch::from_dna(&population, LatentMode::Included).unwrap();
+    ///
+    /// let restored = batch.to_dna(2, &Influence::Included).unwrap();
+    ///
+    /// assert_eq!(restored[3].to_string(), population[3].to_string());
+    /// ```
+    pub fn to_dna(&self, gene_size: u16, influence: &Influence) -> Result<Vec<DNA>, Error> {
+        self.latents
+            .iter()
+            .map(|row| DNA::from_latent_vec(row, gene_size, influence.clone()))
+            .collect()
+    }
+    /// Write the batch in `format`, ids are not supported by `TensorFormat::Npy` and
+    /// `TensorFormat::Npz` is limited to 4 GiB
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Batch, LatentMode, TensorFormat, DNA};
+    ///
+    /// let population = (0..8).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    /// let mut batch = Batch::from_dna(&population, LatentMode::Markers).unwrap();
+    /// batch.ids = Some((100..108).collect());
+    ///
+    /// let data = batch.write(TensorFormat::Safetensors).unwrap();
+    ///
+    /// assert!(batch.write(TensorFormat::Npy).is_err());
+    /// ```
+    pub fn write(&self, format: TensorFormat) -> Result<Vec<u8>, Error> {
+        let mut tensors = vec![(LATENTS, self.latents_tensor()?)];
+        if let Some(ids) = &self.ids {
+            if ids.len() != self.latents.len() {
+                return Err(Error::LengthMismatch {
+                    expected: self.latents.len(),
+                    found: ids.len(),
+                });
+            }
+            tensors.push((IDS, Tensor::from_u64(ids)));
+        }
+        match format {
+            TensorFormat::Npy if tensors.len() > 1 => Err(Error::InvalidSetting(
+                "npy holds only the latents, use npz or safetensors for ids",
+            )),
+            TensorFormat::Npy => Ok(npy::write(&tensors[0].1)),
+            TensorFormat::Npz => npz::write(
+                &tensors
+                    .iter()
+                    .map(|(name, tensor)| (format!("{}.npy", name), npy::write(tensor)))
+                    .collect::<Vec<(String, Vec<u8>)>>(),
+            ),
+            TensorFormat::Safetensors => Ok(safetensors::write(&tensors)),
+        }
+    }
+    /// Read a batch written in `format`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Batch, LatentMode, TensorFormat, DNA};
+    ///
+    /// let population = (0..8).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    /// let batch = Batch::from_dna(&population, LatentMode::Markers).unwrap();
+    /// let data = batch.write(TensorFormat::Npz).unwrap();
+    ///
+    /// let restored = Batch::read(&data, TensorFormat::Npz).unwrap();
+    ///
+    /// assert_eq!(restored, batch);
+    /// ```
+    pub fn read(data: &[u8], format: TensorFormat) -> Result<Batch, Error> {
+        let mut tensors = match format {
+            TensorFormat::Npy => vec![(String::from(LATENTS), npy::read(data)?)],
+            TensorFormat::Npz => npz::read(data)?
+                .into_iter()
+                .filter_map(|(name, file)| {
+                    let name = name.strip_suffix(".npy")?.to_string();
+                    Some(npy::read(file).map(|tensor| (name, tensor)))
+                })
+                .collect::<Result<Vec<(String, Tensor)>, Error>>()?,
+            TensorFormat::Safetensors => safetensors::read(data)?,
+        };
+        let mut take = |name: &str| {
+            tensors
+                .iter()
+                .position(|(n, _)| n == name)
+                .map(|i| tensors.remove(i).1)
+        };
+        let latents = take(LATENTS)
+            .ok_or(Error::Malformed("missing latents tensor"))?
+            .to_f32_rows()?;
+        let ids = take(IDS).map(|ids| ids.to_u64()).transpose()?;
+        if let Some(ids) = &ids {
+            if ids.len() != latents.len() {
+                return Err(Error::LengthMismatch {
+                    expected: latents.len(),
+                    found: ids.len(),
+                });
+            }
+        }
+        Ok(Batch { latents, ids })
+    }
+    fn latents_tensor(&self) -> Result<Tensor, Error> {
+        let dim = self.latents.first().map_or(0, |row| row.len());
+        if let Some(row) = self.latents.iter().find(|row| row.len() != dim) {
+            return Err(Error::LengthMismatch {
+                expected: dim,
+                found: row.len(),
+            });
+        }
+        Ok(Tensor {
+            dtype: DType::F32,
+            shape: vec![self.latents.len(), dim],
+            data: self
+                .latents
+                .iter()
+                .flatten()
+                .flat_map(|v| v.to_le_bytes().to_vec())
+                .collect(),
+        })
+    }
+}
+
+const LATENTS: &str = "latents";
+const IDS: &str = "ids";
+const MAX_EMPTY_ROWS: usize = 1 << 16;
+
+/// Element type of a stored tensor
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum DType {
+    F32,
+    U64,
+}
+
+impl DType {
+    fn size(self) -> usize {
+        match self {
+            DType::F32 => 4,
+            DType::U64 => 8,
+        }
+    }
+}
+
+/// A dense little endian tensor in C order, as every format stores it
+#[derive(Debug, PartialEq)]
+struct Tensor {
+    dtype: DType,
+    shape: Vec<usize>,
+    data: Vec<u8>,
+}
+
+impl Tensor {
+    fn from_u64(values: &[u64]) -> Tensor {
+        Tensor {
+            dtype: DType::U64,
+            shape: vec![values.len()],
+            data: values
+                .iter()
+                .flat_map(|v| v.to_le_bytes().to_vec())
+                .collect(),
+        }
+    }
+    /// Check the data holds exactly the elements of the shape
+    fn validate(&self) -> Result<(), Error> {
+        let expected = self
+            .shape
+            .iter()
+            .try_fold(self.dtype.size(), |size, dim| size.checked_mul(*dim))
+            .ok_or(Error::Malformed("tensor shape is too large"))?;
+        match self.data.len() == expected {
+            true => Ok(()),
+            false => Err(Error::LengthMismatch {
+                expected,
+                found: self.data.len(),
+            }),
+        }
+    }
+    fn to_f32_rows(&self) -> Result<Vec<Vec<f32>>, Error> {
+        if self.dtype != DType::F32 || self.shape.len() != 2 {
+            return Err(Error::Malformed("latents need to be a 2-D f32 tensor"));
+        }
+        let row_size = self.shape[1]
+            .checked_mul(4)
+            .ok_or(Error::Malformed("tensor shape is too large"))?;
+        if row_size == 0 {
+            // Empty rows hold no data, so their count is not bounded by the file size
+            if self.shape[0] > MAX_EMPTY_ROWS {
+                return Err(Error::TooLarge {
+                    limit: MAX_EMPTY_ROWS,
+                    found: self.shape[0],
+                });
+            }
+            return Ok(vec![Vec::new(); self.shape[0]]);
+        }
+        Ok(self
+            .data
+            .chunks(row_size)
+            .map(|row| {
+                row.chunks(4)
+                    .map(|v| f32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+                    .collect()
+            })
+            .collect())
+    }
+    fn to_u64(&self) -> Result<Vec<u64>, Error> {
+        if self.dtype != DType::U64 || self.shape.len() != 1 {
+            return Err(Error::Malformed("ids need to be a 1-D u64 tensor"));
+        }
+        Ok(self
+            .data
+            .chunks(8)
+            .map(|v| {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(v);
+                u64::from_le_bytes(bytes)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Model;
+    fn batch() -> Batch {
+        let population = (0..5).map(|_| DNA::new(3, 2)).collect::<Vec<DNA>>();
+        let mut batch = Batch::from_dna(&population, LatentMode::Included).unwrap();
+        batch.ids = Some(vec![0, 1, u64::MAX, 42, 7]);
+        batch
+    }
+    #[test]
+    fn formats_round_trip() {
+        let batch = batch();
+        for format in [TensorFormat::Npz, TensorFormat::Safetensors].iter() {
+            let data = batch.write(*format).unwrap();
+            assert_eq!(Batch::read(&data, *format).unwrap(), batch);
+        }
+        let latents = Batch {
+            latents: batch.latents.clone(),
+            ids: None,
+        };
+        let data = latents.write(TensorFormat::Npy).unwrap();
+        assert_eq!(Batch::read(&data, TensorFormat::Npy).unwrap(), latents);
+        let empty = Batch::default();
+        for format in [
+            TensorFormat::Npy,
+            TensorFormat::Npz,
+            TensorFormat::Safetensors,
+        ]
+        .iter()
+        {
+            let data = empty.write(*format).unwrap();
+            assert_eq!(Batch::read(&data, *format).unwrap(), empty);
+        }
+    }
+    #[test]
+    fn rows_are_checked() {
+        let mut batch = batch();
+        batch.ids = Some(vec![1, 2]);
+        assert!(batch.write(TensorFormat::Npz).is_err());
+        batch.ids = None;
+        batch.latents[2].pop();
+        assert_eq!(
+            batch.write(TensorFormat::Npy).err(),
+            Some(Error::LengthMismatch {
+                expected: 9,
+                found: 8
+            })
+        );
+        let population = vec![DNA::new(2, 2), DNA::new(2, 3)];
+        assert!(Batch::from_dna(&population, LatentMode::Markers).is_err());
+        let mut population = vec![DNA::new(2, 2), DNA::new(2, 2), DNA::new(2, 2)];
+        population[0].model = Some(Model::new("faces-v2", population[0].get_layout()).unwrap());
+        assert!(Batch::from_dna(&population, LatentMode::Markers).is_ok());
+        population[2].model = Some(Model::new("faces-v3", population[2].get_layout()).unwrap());
+        assert_eq!(
+            Batch::from_dna(&population, LatentMode::Markers).err(),
+            Some(Error::ModelMismatch)
+        );
+    }
+    #[test]
+    fn dna_is_restored() {
+        let population = (0..4).map(|_| DNA::new(2, 3)).collect::<Vec<DNA>>();
+        let batch = Batch::from_dna(&population, LatentMode::Markers).unwrap();
+        let data = batch.write(TensorFormat::Safetensors).unwrap();
+        let restored = Batch::read(&data, TensorFormat::Safetensors)
+            .unwrap()
+            .to_dna(3, &Influence::Default(1.0))
+            .unwrap();
+        for (dna, original) in restored.iter().zip(population.iter()) {
+            assert_eq!(
+                dna.to_latent_vec_with(LatentMode::Markers),
+                original.to_latent_vec_with(LatentMode::Markers)
+            );
+        }
+        assert!(batch.to_dna(4, &Influence::Default(1.0)).is_err());
+    }
+    #[test]
+    fn huge_shapes_are_rejected() {
+        for shape in [
+            vec![1 << 32, 1 << 32],
+            vec![0, usize::MAX],
+            vec![usize::MAX, 0],
+        ]
+        .iter()
+        {
+            let tensor = Tensor {
+                dtype: DType::F32,
+                shape: shape.clone(),
+                data: Vec::new(),
+            };
+            assert!(tensor
+                .validate()
+                .and_then(|_| tensor.to_f32_rows().map(|_| ()))
+                .is_err());
+        }
+    }
+}
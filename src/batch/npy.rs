@@ -0,0 +1,138 @@
+use super::{DType, Tensor};
+use crate::error::Error;
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Write a tensor as a version 1.0 `.npy`, the header padded so the data is 64 byte aligned
+pub fn write(tensor: &Tensor) -> Vec<u8> {
+    let descr = match tensor.dtype {
+        DType::F32 => "<f4",
+        DType::U64 => "<u8",
+    };
+    let mut shape = tensor
+        .shape
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    // Python writes a one element tuple with a trailing comma
+    if tensor.shape.len() == 1 {
+        shape.push(',');
+    }
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': ({}), }}",
+        descr, shape
+    );
+    let length = MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat(length.next_multiple_of(64) - length));
+    header.push('\n');
+    let mut data = MAGIC.to_vec();
+    data.extend_from_slice(&[1, 0]);
+    data.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    data.extend_from_slice(header.as_bytes());
+    data.extend_from_slice(&tensor.data);
+    data
+}
+
+/// Read a little endian, C ordered `.npy` of f32 or u64
+pub fn read(data: &[u8]) -> Result<Tensor, Error> {
+    if data.len() < 10 || &data[..6] != MAGIC {
+        return Err(Error::Malformed("missing npy magic"));
+    }
+    let (length, start): (usize, usize) = match data[6] {
+        1 => (u16::from_le_bytes([data[8], data[9]]) as usize, 10),
+        2 | 3 if data.len() >= 12 => (
+            u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize,
+            12,
+        ),
+        _ => return Err(Error::Malformed("unsupported npy version")),
+    };
+    let end = start
+        .checked_add(length)
+        .ok_or(Error::Malformed("invalid npy header"))?;
+    let header = data
+        .get(start..end)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or(Error::Malformed("invalid npy header"))?;
+    let dtype = match quoted(field(header, "descr")?) {
+        Some("<f4") => DType::F32,
+        Some("<u8") => DType::U64,
+        _ => return Err(Error::Malformed("unsupported npy dtype")),
+    };
+    if !field(header, "fortran_order")?.starts_with("False") {
+        return Err(Error::Malformed("fortran ordered npy is not supported"));
+    }
+    let shape = field(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|s| s.split(')').next())
+        .ok_or(Error::Malformed("invalid npy shape"))?
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|_| Error::Malformed("invalid npy shape"))?;
+    let tensor = Tensor {
+        dtype,
+        shape,
+        data: data[end..].to_vec(),
+    };
+    tensor.validate()?;
+    Ok(tensor)
+}
+
+/// Get the text after `'key':` in the header dict
+fn field<'a>(header: &'a str, key: &str) -> Result<&'a str, Error> {
+    let key = format!("'{}':", key);
+    header
+        .find(&key)
+        .map(|i| header[i + key.len()..].trim_start())
+        .ok_or(Error::Malformed("missing npy header field"))
+}
+
+/// Get the content of a quoted Python string at the start of `data`
+fn quoted(data: &str) -> Option<&str> {
+    let quote = data.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    data[1..].split(quote).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn header_matches_numpy() {
+        let tensor = Tensor {
+            dtype: DType::U64,
+            shape: vec![2],
+            data: vec![0; 16],
+        };
+        let data = write(&tensor);
+        assert_eq!(&data[..8], b"\x93NUMPY\x01\x00");
+        assert!(
+            data[10..].starts_with(b"{'descr': '<u8', 'fortran_order': False, 'shape': (2,), }")
+        );
+        assert_eq!((data.len() - 16) % 64, 0);
+        assert_eq!(data[data.len() - 17], b'\n');
+        assert_eq!(read(&data).unwrap(), tensor);
+    }
+    #[test]
+    fn reads_numpy_header() {
+        // As written by numpy 1.x with np.save of a float32 (1, 2) array
+        let header = "{'descr': '<f4', 'fortran_order': False, 'shape': (1, 2), }";
+        let mut data = b"\x93NUMPY\x01\x00".to_vec();
+        data.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        data.extend_from_slice(header.as_bytes());
+        data.extend_from_slice(&1f32.to_le_bytes());
+        data.extend_from_slice(&(-2f32).to_le_bytes());
+        let tensor = read(&data).unwrap();
+        assert_eq!(tensor.shape, vec![1, 2]);
+        assert_eq!(tensor.data.len(), 8);
+        assert!(read(&data[..data.len() - 1]).is_err());
+        let header = header.replace("<f4", "<f8");
+        let mut data = b"\x93NUMPY\x01\x00".to_vec();
+        data.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        data.extend_from_slice(header.as_bytes());
+        assert!(read(&data).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::error::Error;
+use crate::utils;
+use std::convert::TryFrom;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_DIRECTORY: u32 = 0x0605_4b50;
+// Zip 2.0, the version needed for stored files
+const VERSION: u16 = 20;
+// 1980-01-01, the earliest date a zip can hold
+const DATE: u16 = 0x21;
+
+/// Write files as an uncompressed zip, the layout `np.savez` writes. Zip64 is not written, so
+/// every file and offset needs to stay below 4 GiB.
+pub fn write(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    let mut directory = Vec::new();
+    for (name, file) in files.iter() {
+        let offset = zip32(data.len())?;
+        let size = zip32(file.len())?;
+        let crc = utils::crc32(file);
+        // Fields shared by the local and central header, from version needed to name length
+        let mut common = Vec::new();
+        for value in [VERSION, 0, 0, 0, DATE].iter() {
+            common.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [crc, size, size].iter() {
+            common.extend_from_slice(&value.to_le_bytes());
+        }
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        data.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        data.extend_from_slice(&common);
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(file);
+        directory.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+        directory.extend_from_slice(&VERSION.to_le_bytes());
+        directory.extend_from_slice(&common);
+        // Extra length, comment length, disk, internal and external attributes
+        directory.extend_from_slice(&[0; 12]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+    let directory_offset = zip32(data.len())?;
+    let directory_size = zip32(directory.len())?;
+    data.extend_from_slice(&directory);
+    data.extend_from_slice(&END_OF_DIRECTORY.to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    data.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    data.extend_from_slice(&directory_size.to_le_bytes());
+    data.extend_from_slice(&directory_offset.to_le_bytes());
+    data.extend_from_slice(&[0; 2]);
+    Ok(data)
+}
+
+/// Read the files of an uncompressed zip, following the central directory
+pub fn read(data: &[u8]) -> Result<Vec<(String, &[u8])>, Error> {
+    let malformed = Error::Malformed("invalid npz archive");
+    // The end record is 22 bytes followed by a comment of at most 65535 bytes
+    let end = (0..data.len().saturating_sub(21))
+        .rev()
+        .take(65536)
+        .find(|i| u32_at(data, *i) == Some(END_OF_DIRECTORY))
+        .ok_or(malformed.clone())?;
+    let count = u16_at(data, end + 10).ok_or(malformed.clone())?;
+    let mut cursor = u32_at(data, end + 16).ok_or(malformed.clone())? as usize;
+    (0..count)
+        .map(|_| {
+            if u32_at(data, cursor) != Some(CENTRAL_HEADER) {
+                return Err(malformed.clone());
+            }
+            let field = |offset: usize| u16_at(data, cursor + offset).ok_or(malformed.clone());
+            let method = field(10)?;
+            let (name_length, extra_length, comment_length) = (field(28)?, field(30)?, field(32)?);
+            let crc = u32_at(data, cursor + 16).ok_or(malformed.clone())?;
+            let mut size = u32_at(data, cursor + 24).ok_or(malformed.clone())? as u64;
+            let mut offset = u32_at(data, cursor + 42).ok_or(malformed.clone())? as u64;
+            let name_start = cursor + 46;
+            let extra_start = name_start + name_length as usize;
+            let name = data
+                .get(name_start..extra_start)
+                .and_then(|n| std::str::from_utf8(n).ok())
+                .ok_or(malformed.clone())?;
+            let extra = data
+                .get(extra_start..extra_start + extra_length as usize)
+                .ok_or(malformed.clone())?;
+            zip64(extra, &mut size, &mut offset);
+            cursor = extra_start + extra_length as usize + comment_length as usize;
+            if method != 0 {
+                return Err(Error::Malformed("compressed npz is not supported"));
+            }
+            // Zip64 values are read from the file, so any of them can be out of range
+            let offset = usize::try_from(offset).map_err(|_| malformed.clone())?;
+            let size = usize::try_from(size).map_err(|_| malformed.clone())?;
+            if u32_at(data, offset) != Some(LOCAL_HEADER) {
+                return Err(malformed.clone());
+            }
+            let local_name = u16_at(data, offset + 26).ok_or(malformed.clone())? as usize;
+            let local_extra = u16_at(data, offset + 28).ok_or(malformed.clone())? as usize;
+            let start = offset + 30 + local_name + local_extra;
+            let file = start
+                .checked_add(size)
+                .and_then(|end| data.get(start..end))
+                .ok_or(malformed.clone())?;
+            if utils::crc32(file) != crc {
+                return Err(Error::ChecksumMismatch);
+            }
+            Ok((String::from(name), file))
+        })
+        .collect()
+}
+
+/// Replace sizes and offsets marked as too large with the values of the zip64 extra field,
+/// as numpy writes them
+fn zip64(extra: &[u8], size: &mut u64, offset: &mut u64) {
+    let mut cursor = 0;
+    while let (Some(id), Some(length)) = (u16_at(extra, cursor), u16_at(extra, cursor + 2)) {
+        if id == 1 {
+            let mut value = cursor + 4;
+            let mut next = || {
+                let bytes = extra.get(value..value + 8)?;
+                value += 8;
+                let mut buffer = [0; 8];
+                buffer.copy_from_slice(bytes);
+                Some(u64::from_le_bytes(buffer))
+            };
+            // Uncompressed size, compressed size and offset, only those set to the maximum
+            if *size == u32::MAX as u64 {
+                *size = next().unwrap_or(*size);
+                next();
+            }
+            if *offset == u32::MAX as u64 {
+                *offset = next().unwrap_or(*offset);
+            }
+        }
+        cursor += 4 + length as usize;
+    }
+}
+
+/// Convert a size or offset to its 32 bit field, the maximum is reserved to mark zip64 values
+fn zip32(value: usize) -> Result<u32, Error> {
+    match value < u32::MAX as usize {
+        true => Ok(value as u32),
+        false => Err(Error::TooLarge {
+            limit: u32::MAX as usize - 1,
+            found: value,
+        }),
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn can_be_written_and_read() {
+        let files = vec![
+            (String::from("latents.npy"), vec![1, 2, 3]),
+            (String::from("ids.npy"), Vec::new()),
+        ];
+        let data = write(&files).unwrap();
+        let read_files = read(&data).unwrap();
+        assert_eq!(read_files.len(), 2);
+        assert_eq!(read_files[0], ("latents.npy".to_string(), &[1u8, 2, 3][..]));
+        assert_eq!(read_files[1].1.len(), 0);
+        let mut corrupt = data.clone();
+        corrupt[42] ^= 1;
+        assert_eq!(read(&corrupt).err(), Some(Error::ChecksumMismatch));
+        assert!(read(&data[..data.len() - 1]).is_err());
+    }
+    #[test]
+    fn sizes_need_to_fit_without_zip64() {
+        assert_eq!(zip32(u32::MAX as usize - 1), Ok(u32::MAX - 1));
+        assert_eq!(
+            zip32(u32::MAX as usize),
+            Err(Error::TooLarge {
+                limit: u32::MAX as usize - 1,
+                found: u32::MAX as usize
+            })
+        );
+    }
+}
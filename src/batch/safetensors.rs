@@ -0,0 +1,346 @@
+use super::{DType, Tensor};
+use crate::error::Error;
+
+/// Write named tensors as safetensors, a JSON header padded to 8 bytes followed by the data
+pub fn write(tensors: &[(&str, Tensor)]) -> Vec<u8> {
+    let mut offset = 0;
+    let entries = tensors
+        .iter()
+        .map(|(name, tensor)| {
+            let dtype = match tensor.dtype {
+                DType::F32 => "F32",
+                DType::U64 => "U64",
+            };
+            let shape = tensor
+                .shape
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            let entry = format!(
+                "\"{}\":{{\"dtype\":\"{}\",\"shape\":[{}],\"data_offsets\":[{},{}]}}",
+                name,
+                dtype,
+                shape,
+                offset,
+                offset + tensor.data.len()
+            );
+            offset += tensor.data.len();
+            entry
+        })
+        .collect::<Vec<String>>();
+    let mut header = format!("{{{}}}", entries.join(","));
+    header.push_str(&" ".repeat(header.len().next_multiple_of(8) - header.len()));
+    let mut data = (header.len() as u64).to_le_bytes().to_vec();
+    data.extend_from_slice(header.as_bytes());
+    for (_, tensor) in tensors.iter() {
+        data.extend_from_slice(&tensor.data);
+    }
+    data
+}
+
+/// Read the f32 and u64 tensors of a safetensors file, `__metadata__` and tensors of other
+/// dtypes are skipped
+pub fn read(data: &[u8]) -> Result<Vec<(String, Tensor)>, Error> {
+    let malformed = Error::Malformed("invalid safetensors header");
+    if data.len() < 8 {
+        return Err(malformed);
+    }
+    let mut length = [0; 8];
+    length.copy_from_slice(&data[..8]);
+    let length = u64::from_le_bytes(length);
+    if length > (data.len() - 8) as u64 {
+        return Err(malformed);
+    }
+    let header =
+        std::str::from_utf8(&data[8..8 + length as usize]).map_err(|_| malformed.clone())?;
+    let body = &data[8 + length as usize..];
+    let entries = match Json::parse(header)? {
+        Json::Object(entries) => entries,
+        _ => return Err(malformed),
+    };
+    entries
+        .into_iter()
+        .filter(|(name, _)| name != "__metadata__")
+        .filter_map(|(name, entry)| {
+            let dtype = match entry.get("dtype") {
+                Some(Json::String(dtype)) if dtype == "F32" => DType::F32,
+                Some(Json::String(dtype)) if dtype == "U64" => DType::U64,
+                Some(Json::String(_)) => return None,
+                _ => return Some(Err(malformed.clone())),
+            };
+            Some(tensor(body, dtype, &entry).map(|tensor| (name, tensor)))
+        })
+        .collect()
+}
+
+/// Build one tensor from its header entry and the data after the header
+fn tensor(body: &[u8], dtype: DType, entry: &Json) -> Result<Tensor, Error> {
+    let malformed = Error::Malformed("invalid safetensors header");
+    let shape = entry
+        .get("shape")
+        .and_then(Json::as_usizes)
+        .ok_or(malformed.clone())?;
+    let offsets = entry
+        .get("data_offsets")
+        .and_then(Json::as_usizes)
+        .filter(|o| o.len() == 2 && o[0] <= o[1])
+        .ok_or(malformed.clone())?;
+    let tensor = Tensor {
+        dtype,
+        shape,
+        data: body
+            .get(offsets[0]..offsets[1])
+            .ok_or(malformed.clone())?
+            .to_vec(),
+    };
+    tensor.validate()?;
+    Ok(tensor)
+}
+
+/// The JSON values a safetensors header uses
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(data: &str) -> Result<Json, Error> {
+        let mut parser = Parser {
+            data: data.as_bytes(),
+            cursor: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.whitespace();
+        match parser.cursor == parser.data.len() {
+            true => Ok(value),
+            false => Err(Error::Malformed("trailing data after JSON")),
+        }
+    }
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+    fn as_usizes(&self) -> Option<Vec<usize>> {
+        match self {
+            Json::Array(values) => values
+                .iter()
+                .map(|v| match v {
+                    Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Safetensors headers nest three deep, an object of tensors holding their shape arrays
+const MAX_DEPTH: usize = 3;
+
+struct Parser<'a> {
+    data: &'a [u8],
+    cursor: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn value(&mut self) -> Result<Json, Error> {
+        self.whitespace();
+        match self.data.get(self.cursor) {
+            Some(b'{' | b'[') if self.depth == MAX_DEPTH => {
+                Err(Error::Malformed("JSON nested too deeply"))
+            }
+            Some(b'{') => self.nested(Parser::object),
+            Some(b'[') => self.nested(Parser::array),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(_) => self.number(),
+            None => Err(Error::Malformed("unexpected end of JSON")),
+        }
+    }
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, Error>) -> Result<Json, Error> {
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+    fn object(&mut self) -> Result<Json, Error> {
+        self.cursor += 1;
+        let mut entries = Vec::new();
+        self.whitespace();
+        if self.eat(b'}') {
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            if !self.eat(b':') {
+                return Err(Error::Malformed("expected ':' in JSON object"));
+            }
+            entries.push((key, self.value()?));
+            self.whitespace();
+            if self.eat(b'}') {
+                return Ok(Json::Object(entries));
+            }
+            if !self.eat(b',') {
+                return Err(Error::Malformed("expected ',' in JSON object"));
+            }
+        }
+    }
+    fn array(&mut self) -> Result<Json, Error> {
+        self.cursor += 1;
+        let mut values = Vec::new();
+        self.whitespace();
+        if self.eat(b']') {
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.whitespace();
+            if self.eat(b']') {
+                return Ok(Json::Array(values));
+            }
+            if !self.eat(b',') {
+                return Err(Error::Malformed("expected ',' in JSON array"));
+            }
+        }
+    }
+    fn string(&mut self) -> Result<String, Error> {
+        let malformed = Error::Malformed("invalid JSON string");
+        if !self.eat(b'"') {
+            return Err(malformed);
+        }
+        let mut bytes = Vec::new();
+        loop {
+            match self.next().ok_or(malformed.clone())? {
+                b'"' => return String::from_utf8(bytes).map_err(|_| malformed),
+                b'\\' => {
+                    let escaped = match self.next().ok_or(malformed.clone())? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let hex = self
+                                .data
+                                .get(self.cursor..self.cursor + 4)
+                                .and_then(|h| std::str::from_utf8(h).ok())
+                                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                                .ok_or(malformed.clone())?;
+                            self.cursor += 4;
+                            // Surrogate pairs are not needed for tensor names
+                            std::char::from_u32(hex).ok_or(malformed.clone())?
+                        }
+                        _ => return Err(malformed),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                byte => bytes.push(byte),
+            }
+        }
+    }
+    fn number(&mut self) -> Result<Json, Error> {
+        let start = self.cursor;
+        while let Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') = self.data.get(self.cursor)
+        {
+            self.cursor += 1;
+        }
+        std::str::from_utf8(&self.data[start..self.cursor])
+            .ok()
+            .and_then(|n| n.parse::<f64>().ok())
+            .map(Json::Number)
+            .ok_or(Error::Malformed("invalid JSON number"))
+    }
+    fn literal(&mut self, literal: &str, value: Json) -> Result<Json, Error> {
+        match self.data[self.cursor..].starts_with(literal.as_bytes()) {
+            true => {
+                self.cursor += literal.len();
+                Ok(value)
+            }
+            false => Err(Error::Malformed("invalid JSON literal")),
+        }
+    }
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.data.get(self.cursor) {
+            self.cursor += 1;
+        }
+    }
+    fn eat(&mut self, byte: u8) -> bool {
+        match self.data.get(self.cursor) == Some(&byte) {
+            true => {
+                self.cursor += 1;
+                true
+            }
+            false => false,
+        }
+    }
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.data.get(self.cursor)?;
+        self.cursor += 1;
+        Some(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn header_is_parsed() {
+        let json = Json::parse(
+            r#" {"__metadata__": {"format": "pt"}, "ab": [1, 2.5e1, true, null, "\"x\""]} "#,
+        )
+        .unwrap();
+        assert_eq!(
+            json.get("ab"),
+            Some(&Json::Array(vec![
+                Json::Number(1.0),
+                Json::Number(25.0),
+                Json::Bool(true),
+                Json::Null,
+                Json::String(String::from("\"x\""))
+            ]))
+        );
+        assert!(Json::parse("{\"a\": 1,}").is_err());
+        assert!(Json::parse("[1, 2] 3").is_err());
+        assert!(Json::parse("\"open").is_err());
+        assert!(Json::parse("[[[1]]]").is_ok());
+        assert_eq!(
+            Json::parse("[[[[1]]]]"),
+            Err(Error::Malformed("JSON nested too deeply"))
+        );
+    }
+    #[test]
+    fn reads_torch_layout() {
+        // As written by safetensors.torch.save_file, with metadata and a tensor it ignores
+        let header = r#"{"__metadata__":{"format":"pt"},"latents":{"dtype":"F32","shape":[1,2],"data_offsets":[0,8]},"mask":{"dtype":"F16","shape":[2],"data_offsets":[8,12]}}"#;
+        let mut data = (header.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(header.as_bytes());
+        data.extend_from_slice(&1f32.to_le_bytes());
+        data.extend_from_slice(&2f32.to_le_bytes());
+        data.extend_from_slice(&[0, 60, 0, 64]);
+        let tensors = read(&data).unwrap();
+        assert_eq!(tensors.len(), 1);
+        assert_eq!(tensors[0].0, "latents");
+        assert_eq!(tensors[0].1.shape, vec![1, 2]);
+        assert!(read(&data[..data.len() - 5]).is_err());
+        let written = write(&[("latents", tensors.into_iter().next().unwrap().1)]);
+        assert_eq!(u64::from_le_bytes([written[0], 0, 0, 0, 0, 0, 0, 0]) % 8, 0);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::error::Error;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [
+    0x3b6a_57b2,
+    0x2650_8e6d,
+    0x1ea1_19fa,
+    0x3d42_33dd,
+    0x2a14_62b3,
+];
+// Bech32m constant from BIP350
+const CONSTANT: u32 = 0x2bc8_30a3;
+
+/// Encode bytes as Bech32m with the human readable prefix `hrp`, which needs to be non-empty
+/// printable ASCII without spaces.
+///
+/// The 90 char limit of BIP173 is not enforced, DNA is usually longer. The checksum still
+/// detects any error, but only guarantees locating a single wrong char.
+pub fn encode(hrp: &str, data: &[u8]) -> Result<String, Error> {
+    if hrp.is_empty() {
+        return Err(Error::InvalidSetting("prefix needs to be non-empty"));
+    }
+    check_hrp(hrp)?;
+    let hrp = hrp.to_lowercase();
+    let values = to_base32(data);
+    let mut checked = hrp_expand(&hrp);
+    checked.extend_from_slice(&values);
+    checked.extend_from_slice(&[0; 6]);
+    let residue = polymod(&checked) ^ CONSTANT;
+    let checksum = (0..6).map(|i| ((residue >> (5 * (5 - i))) & 31) as u8);
+    Ok(format!(
+        "{}1{}",
+        hrp,
+        values
+            .iter()
+            .copied()
+            .chain(checksum)
+            .map(|v| CHARSET[v as usize] as char)
+            .collect::<String>()
+    ))
+}
+
+/// Decode a Bech32m string, return the prefix and bytes.
+///
+/// When the checksum fails, the positions of chars that would fix it alone are reported.
+pub fn decode(data: &str) -> Result<(String, Vec<u8>), Error> {
+    if data.chars().any(|c| c.is_ascii_lowercase()) && data.chars().any(|c| c.is_ascii_uppercase())
+    {
+        return Err(Error::Malformed("mixed case"));
+    }
+    let data = data.to_lowercase();
+    let separator = data
+        .rfind('1')
+        .ok_or(Error::Malformed("missing separator"))?;
+    if separator == 0 || data.len() < separator + 7 {
+        return Err(Error::Malformed("missing prefix or checksum"));
+    }
+    let (hrp, rest) = (&data[..separator], &data[separator + 1..]);
+    check_hrp(hrp)?;
+    let values =
+        rest.bytes()
+            .enumerate()
+            .map(|(i, c)| {
+                CHARSET.iter().position(|v| *v == c).map(|v| v as u8).ok_or(
+                    Error::InvalidCharacter {
+                        position: separator + 1 + i,
+                    },
+                )
+            })
+            .collect::<Result<Vec<u8>, Error>>()?;
+    let mut checked = hrp_expand(hrp);
+    checked.extend_from_slice(&values);
+    let residue = polymod(&checked) ^ CONSTANT;
+    if residue != 0 {
+        let positions = locate_errors(residue, values.len())
+            .iter()
+            .map(|p| separator + 1 + p)
+            .collect::<Vec<usize>>();
+        return match positions.is_empty() {
+            true => Err(Error::ChecksumMismatch),
+            false => Err(Error::Typo { positions }),
+        };
+    }
+    let bytes =
+        from_base32(&values[..values.len() - 6]).ok_or(Error::Malformed("invalid padding"))?;
+    Ok((String::from(hrp), bytes))
+}
+
+/// Check that every byte of the prefix is printable ASCII, BIP173 allows 33 to 126
+fn check_hrp(hrp: &str) -> Result<(), Error> {
+    match hrp.bytes().position(|b| !(33..=126).contains(&b)) {
+        Some(position) => Err(Error::InvalidCharacter { position }),
+        None => Ok(()),
+    }
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    values.iter().fold(1, |check, value| step(check, *value))
+}
+
+fn step(check: u32, value: u8) -> u32 {
+    let top = check >> 25;
+    let mut check = (check & 0x1ff_ffff) << 5 ^ value as u32;
+    for (i, generator) in GENERATOR.iter().enumerate() {
+        if (top >> i) & 1 == 1 {
+            check ^= generator;
+        }
+    }
+    check
+}
+
+/// Find the positions where changing one value alone gives a zero residue.
+///
+/// The checksum is linear, so changing value `i` by `e` changes the residue by the state of
+/// feeding `e` followed by `length - 1 - i` zeros. That is walked once for every `e`.
+fn locate_errors(residue: u32, length: usize) -> Vec<usize> {
+    let mut states = (1..32u8).map(|e| step(0, e)).collect::<Vec<u32>>();
+    let mut positions = Vec::new();
+    for offset in 0..length {
+        if states.contains(&residue) {
+            positions.push(length - 1 - offset);
+        }
+        for state in states.iter_mut() {
+            *state = step(*state, 0);
+        }
+    }
+    positions.reverse();
+    positions
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut values = hrp.bytes().map(|b| b >> 5).collect::<Vec<u8>>();
+    values.push(0);
+    values.extend(hrp.bytes().map(|b| b & 31));
+    values
+}
+
+fn to_base32(data: &[u8]) -> Vec<u8> {
+    let mut values = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in data.iter() {
+        buffer = buffer << 8 | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            values.push((buffer >> bits & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        values.push((buffer << (5 - bits) & 31) as u8);
+    }
+    values
+}
+
+fn from_base32(values: &[u8]) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for value in values.iter() {
+        buffer = buffer << 5 | *value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits & 0xff) as u8);
+        }
+    }
+    // Padding needs to be less than a byte and all zero
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn bip350_vector() {
+        // Valid Bech32m string from BIP350
+        assert!(decode("A1LQFN3A").is_ok());
+        assert!(decode("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx").is_ok());
+    }
+    #[test]
+    fn can_be_encoded_and_back() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        let encoded = encode("dna", &data).unwrap();
+        assert!(encoded.starts_with("dna1"));
+        assert_eq!(
+            decode(&encoded).unwrap(),
+            (String::from("dna"), data.clone())
+        );
+        assert_eq!(decode(&encoded.to_uppercase()).unwrap().1, data);
+    }
+    #[test]
+    fn locates_typo() {
+        let encoded = encode("dna", &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let mut typo = encoded.clone().into_bytes();
+        typo[9] = if typo[9] == b'q' { b'p' } else { b'q' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert_eq!(
+            decode(&typo).err(),
+            Some(Error::Typo { positions: vec![9] })
+        );
+    }
+    #[test]
+    fn rejects_invalid_characters() {
+        assert_eq!(
+            decode("dna1bqqqqqq").err(),
+            Some(Error::InvalidCharacter { position: 4 })
+        );
+    }
+    #[test]
+    fn encoded_prefix_can_be_decoded() {
+        assert!(encode("", &[1]).is_err());
+        assert_eq!(
+            encode("my dna", &[1]).err(),
+            Some(Error::InvalidCharacter { position: 2 })
+        );
+        assert!(encode("dnä", &[1]).is_err());
+        let encoded = encode("a1b", &[1]).unwrap();
+        assert_eq!(decode(&encoded).unwrap(), (String::from("a1b"), vec![1]));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::dna::DNA;
+use crate::error::Error;
+use crate::utils::{self, Reader};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+/// Identifies the transcript bytes and their version
+const MAGIC: &[u8; 8] = b"GNMBRED1";
+/// Separates the breeding seed from other uses of SHA-256
+const DOMAIN: &[u8] = b"genome breeding seed v1";
+
+/// A provably fair breeding, everything needed to re-run the merge offline.
+///
+/// 1. The server picks a secret `server_seed` and publishes `Transcript::commit(&server_seed)`.
+/// 2. The player sends `player_entropy` after seeing the commitment.
+/// 3. The server breeds with `Transcript::breed`, revealing its seed in the transcript.
+///
+/// The child is `DNA::merge_with_rng` seeded by `get_seed`, a SHA-256 over both seeds and both
+/// parents. The server cannot pick a seed after seeing the entropy and the player cannot
+/// predict the seed, so neither side can choose the child alone.
+#[derive(Clone)]
+pub struct Transcript {
+    pub left: DNA,
+    pub right: DNA,
+    pub mutate: bool,
+    pub commitment: [u8; 32],
+    pub server_seed: [u8; 32],
+    pub player_entropy: Vec<u8>,
+    pub child: DNA,
+}
+
+impl Transcript {
+    /// Get the commitment to publish before the player sends entropy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Transcript;
+    ///
+    /// let commitment = Transcript::commit(&[7; 32]);
+    /// ```
+    pub fn commit(server_seed: &[u8; 32]) -> [u8; 32] {
+        Sha256::digest(server_seed).into()
+    }
+    /// Breed two `DNA` from the revealed server seed and the player entropy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Transcript, DNA};
+    ///
+    /// let server_seed = [7; 32];
+    /// let commitment = Transcript::commit(&server_seed);
+    /// let player_entropy = b"player entropy";
+    ///
+    /// let transcript =
+    ///     Transcript::breed(DNA::new(2, 2), DNA::new(2, 2), true, server_seed, player_entropy)
+    ///         .unwrap();
+    ///
+    /// assert_eq!(transcript.commitment, commitment);
+    /// ```
+    pub fn breed(
+        left: DNA,
+        right: DNA,
+        mutate: bool,
+        server_seed: [u8; 32],
+        player_entropy: &[u8],
+    ) -> Option<Transcript> {
+        let mut transcript = Transcript {
+            child: DNA::from_genes(left.gene_size, Vec::new()),
+            left,
+            right,
+            mutate,
+            commitment: Transcript::commit(&server_seed),
+            server_seed,
+            player_entropy: player_entropy.to_vec(),
+        };
+        transcript.child = transcript.rerun()?;
+        Some(transcript)
+    }
+    /// Get the seed of the merge, SHA-256 over both seeds, both parent hashes and `mutate`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Transcript, DNA};
+    ///
+    /// let transcript =
+    ///     Transcript::breed(DNA::new(2, 2), DNA::new(2, 2), false, [7; 32], b"entropy").unwrap();
+    ///
+    /// let seed = transcript.get_seed();
+    /// ```
+    pub fn get_seed(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN);
+        hasher.update(self.server_seed);
+        hasher.update((self.player_entropy.len() as u64).to_be_bytes());
+        hasher.update(&self.player_entropy);
+        hasher.update(self.left.get_hash());
+        hasher.update(self.right.get_hash());
+        hasher.update([self.mutate as u8]);
+        hasher.finalize().into()
+    }
+    /// Check the seed matches the commitment and re-running the merge gives the same child
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Transcript, DNA};
+    ///
+    /// let transcript =
+    ///     Transcript::breed(DNA::new(2, 2), DNA::new(2, 2), true, [7; 32], b"entropy").unwrap();
+    ///
+    /// assert!(transcript.verify().is_ok());
+    /// ```
+    pub fn verify(&self) -> Result<(), Error> {
+        if Transcript::commit(&self.server_seed) != self.commitment {
+            return Err(Error::VerificationFailed(
+                "server seed does not match the commitment",
+            ));
+        }
+        let child = self
+            .rerun()
+            .ok_or(Error::VerificationFailed("parents cannot be merged"))?;
+        if child.to_bytes() != self.child.to_bytes() {
+            return Err(Error::VerificationFailed("child does not match the merge"));
+        }
+        Ok(())
+    }
+    /// Convert transcript to bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Transcript, DNA};
+    ///
+    /// let transcript =
+    ///     Transcript::breed(DNA::new(2, 2), DNA::new(2, 2), true, [7; 32], b"entropy").unwrap();
+    ///
+    /// let transcript_bytes = transcript.to_bytes();
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&self.commitment);
+        data.extend_from_slice(&self.server_seed);
+        data.push(self.mutate as u8);
+        for field in [
+            self.player_entropy.clone(),
+            self.left.to_bytes(),
+            self.right.to_bytes(),
+            self.child.to_bytes(),
+        ]
+        .iter()
+        {
+            utils::write_varint(&mut data, field.len() as u64);
+            data.extend_from_slice(field);
+        }
+        data
+    }
+    /// Convert bytes to transcript, nothing is checked until `verify`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Transcript, DNA};
+    ///
+    /// let transcript =
+    ///     Transcript::breed(DNA::new(2, 2), DNA::new(2, 2), true, [7; 32], b"entropy").unwrap();
+    ///
+    /// let transcript_copy = Transcript::from_bytes(&transcript.to_bytes()).unwrap();
+    /// ```
+    pub fn from_bytes(data: &[u8]) -> Result<Transcript, Error> {
+        let rest = data
+            .strip_prefix(&MAGIC[..])
+            .ok_or(Error::Malformed("missing transcript magic"))?;
+        let mut reader = Reader { data: rest };
+        let mut commitment = [0; 32];
+        commitment.copy_from_slice(reader.read_bytes(32)?);
+        let mut server_seed = [0; 32];
+        server_seed.copy_from_slice(reader.read_bytes(32)?);
+        let mutate = match reader.read_bytes(1)?[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::Malformed("mutate needs to be 0 or 1")),
+        };
+        let player_entropy = reader.read_prefixed()?.to_vec();
+        let left = DNA::from_bytes(reader.read_prefixed()?)?;
+        let right = DNA::from_bytes(reader.read_prefixed()?)?;
+        let child = DNA::from_bytes(reader.read_prefixed()?)?;
+        if !reader.data.is_empty() {
+            return Err(Error::Malformed("unexpected data after transcript"));
+        }
+        Ok(Transcript {
+            left,
+            right,
+            mutate,
+            commitment,
+            server_seed,
+            player_entropy,
+            child,
+        })
+    }
+    /// Convert transcript to a hex string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Transcript, DNA};
+    ///
+    /// let transcript =
+    ///     Transcript::breed(DNA::new(2, 2), DNA::new(2, 2), true, [7; 32], b"entropy").unwrap();
+    ///
+    /// let transcript_str = transcript.to_string();
+    /// ```
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        utils::bytes_to_string(&self.to_bytes())
+    }
+    fn rerun(&self) -> Option<DNA> {
+        DNA::merge_with_rng(
+            self.left.clone(),
+            self.right.clone(),
+            self.mutate,
+            &mut ChaCha20Rng::from_seed(self.get_seed()),
+        )
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = Error;
+    fn from_str(data: &str) -> Result<Transcript, Error> {
+        if data.len() % 2 != 0 || !data.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::Malformed("transcript needs to be hex"));
+        }
+        Transcript::from_bytes(&utils::bytes_from_str(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dna::Layout;
+    fn breed(player_entropy: &[u8]) -> Transcript {
+        // Fixed parents with enough genes that two seeds never pick the same child
+        let layout = Layout {
+            pool_size: 64,
+            gene_size: 4,
+        };
+        let left = DNA::derive(b"left", layout);
+        let right = DNA::derive(b"right", layout);
+        Transcript::breed(left, right, true, [9; 32], player_entropy).unwrap()
+    }
+    #[test]
+    fn offline_transcript_verifies() {
+        let transcript = breed(b"entropy");
+        let transcript_copy: Transcript = transcript.to_string().parse().unwrap();
+        assert!(transcript_copy.verify().is_ok());
+        assert_eq!(
+            transcript_copy.child.to_string(),
+            transcript.child.to_string()
+        );
+    }
+    #[test]
+    fn same_inputs_give_same_child() {
+        let transcript = breed(b"entropy");
+        let again = Transcript::breed(
+            transcript.left.clone(),
+            transcript.right.clone(),
+            true,
+            [9; 32],
+            b"entropy",
+        )
+        .unwrap();
+        assert_eq!(again.child.to_string(), transcript.child.to_string());
+        assert_ne!(breed(b"other entropy").get_seed(), transcript.get_seed());
+    }
+    #[test]
+    fn child_is_stable() {
+        // Golden vector, changing this breaks every published transcript
+        let layout = Layout {
+            pool_size: 6,
+            gene_size: 1,
+        };
+        let transcript = Transcript::breed(
+            DNA::derive(b"left", layout),
+            DNA::derive(b"right", layout),
+            true,
+            [9; 32],
+            b"entropy",
+        )
+        .unwrap();
+        assert_eq!(
+            transcript.child.to_string(),
+            "bda7ca18000600013eda1439bf817bd9bf2cfe4d4006ac39bf2cde6fbf8457393e279fd5bd4a43653f9fea503e24227fbeba55febec04ecb"
+        );
+    }
+    #[test]
+    fn seed_tampering_is_detected() {
+        let mut transcript = breed(b"entropy");
+        transcript.server_seed[0] ^= 1;
+        assert_eq!(
+            transcript.verify(),
+            Err(Error::VerificationFailed(
+                "server seed does not match the commitment"
+            ))
+        );
+    }
+    #[test]
+    fn commitment_tampering_is_detected() {
+        let mut transcript = breed(b"entropy");
+        transcript.commitment[0] ^= 1;
+        assert_eq!(
+            transcript.verify(),
+            Err(Error::VerificationFailed(
+                "server seed does not match the commitment"
+            ))
+        );
+    }
+    #[test]
+    fn entropy_tampering_is_detected() {
+        let mut transcript = breed(b"entropy");
+        transcript.player_entropy.push(0);
+        assert_eq!(
+            transcript.verify(),
+            Err(Error::VerificationFailed("child does not match the merge"))
+        );
+    }
+    #[test]
+    fn child_tampering_is_detected() {
+        let mut transcript = breed(b"entropy");
+        transcript.child.genes[0].markers[0].value += 1.0;
+        assert_eq!(
+            transcript.verify(),
+            Err(Error::VerificationFailed("child does not match the merge"))
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Signed DNA, enabled with the `certificate` feature.
+use crate::dna::DNA;
+use crate::error::Error;
+use crate::utils::{self, Reader};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+/// Identifies the canonical form and its version
+const MAGIC: &[u8; 8] = b"GNMCERT1";
+
+/// A `DNA` signed with Ed25519 by its issuer.
+///
+/// The signature covers the canonical bytes from `signed_bytes`: the magic `GNMCERT1`, the
+/// varint length and bytes of `DNA::to_bytes`, the 8 byte key id, the varint number of parents
+/// and the 32 byte hash of each parent. The certificate is written as the canonical bytes
+/// followed by the 64 byte signature.
+#[derive(Clone)]
+pub struct Certificate {
+    pub dna: DNA,
+    pub key_id: [u8; 8],
+    pub parents: Vec<[u8; 32]>,
+    pub signature: [u8; 64],
+}
+
+impl Certificate {
+    /// Sign a DNA, `parents` are the `DNA::get_hash` of the parents it was bred from
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey, DNA};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let dna = DNA::new(2, 2);
+    ///
+    /// let certificate = Certificate::sign(dna, Vec::new(), &key);
+    /// ```
+    pub fn sign(dna: DNA, parents: Vec<[u8; 32]>, key: &SigningKey) -> Certificate {
+        let mut certificate = Certificate {
+            dna,
+            key_id: Certificate::key_id(&key.verifying_key()),
+            parents,
+            signature: [0; 64],
+        };
+        certificate.signature = key.sign(&certificate.signed_bytes()).to_bytes();
+        certificate
+    }
+    /// Get the key id of a public key, the first 8 bytes of its SHA-256 hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    ///
+    /// let key_id = Certificate::key_id(&key.verifying_key());
+    /// ```
+    pub fn key_id(key: &VerifyingKey) -> [u8; 8] {
+        let mut key_id = [0; 8];
+        key_id.copy_from_slice(&Sha256::digest(key.as_bytes())[..8]);
+        key_id
+    }
+    /// Check the certificate was signed by `key` and nothing was changed since
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey, DNA};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let certificate = Certificate::sign(DNA::new(2, 2), Vec::new(), &key);
+    ///
+    /// assert!(certificate.verify(&key.verifying_key()).is_ok());
+    /// ```
+    pub fn verify(&self, key: &VerifyingKey) -> Result<(), Error> {
+        if self.key_id != Certificate::key_id(key) {
+            return Err(Error::UnknownKey);
+        }
+        key.verify_strict(
+            &self.signed_bytes(),
+            &Signature::from_bytes(&self.signature),
+        )
+        .map_err(|_| Error::InvalidSignature)
+    }
+    /// Get the canonical bytes covered by the signature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey, DNA};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let certificate = Certificate::sign(DNA::new(2, 2), Vec::new(), &key);
+    ///
+    /// assert!(certificate.signed_bytes().starts_with(b"GNMCERT1"));
+    /// ```
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let dna_bytes = self.dna.to_bytes();
+        let mut data = MAGIC.to_vec();
+        utils::write_varint(&mut data, dna_bytes.len() as u64);
+        data.extend_from_slice(&dna_bytes);
+        data.extend_from_slice(&self.key_id);
+        utils::write_varint(&mut data, self.parents.len() as u64);
+        for parent in self.parents.iter() {
+            data.extend_from_slice(parent);
+        }
+        data
+    }
+    /// Convert certificate to bytes, the canonical bytes followed by the signature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey, DNA};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let certificate = Certificate::sign(DNA::new(2, 2), Vec::new(), &key);
+    ///
+    /// let certificate_bytes = certificate.to_bytes();
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.signed_bytes(), self.signature.to_vec()].concat()
+    }
+    /// Convert bytes to certificate, the signature is not checked until `verify`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey, DNA};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let certificate = Certificate::sign(DNA::new(2, 2), Vec::new(), &key);
+    ///
+    /// let certificate_copy = Certificate::from_bytes(&certificate.to_bytes()).unwrap();
+    /// ```
+    pub fn from_bytes(data: &[u8]) -> Result<Certificate, Error> {
+        let rest = data
+            .strip_prefix(&MAGIC[..])
+            .ok_or(Error::Malformed("missing certificate magic"))?;
+        let mut reader = Reader { data: rest };
+        let dna_bytes = reader.read_prefixed()?;
+        let key_bytes = reader.read_bytes(8)?;
+        let count = reader.read_varint()? as usize;
+        let parent_bytes = reader.read_bytes(count.saturating_mul(32))?;
+        if reader.data.len() != 64 {
+            return Err(Error::LengthMismatch {
+                expected: 64,
+                found: reader.data.len(),
+            });
+        }
+        let mut key_id = [0; 8];
+        key_id.copy_from_slice(key_bytes);
+        let mut signature = [0; 64];
+        signature.copy_from_slice(reader.data);
+        Ok(Certificate {
+            dna: DNA::from_bytes(dna_bytes)?,
+            key_id,
+            parents: parent_bytes
+                .chunks(32)
+                .map(|c| {
+                    let mut parent = [0; 32];
+                    parent.copy_from_slice(c);
+                    parent
+                })
+                .collect(),
+            signature,
+        })
+    }
+    /// Convert certificate to a hex string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Certificate, SigningKey, DNA};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let certificate = Certificate::sign(DNA::new(2, 2), Vec::new(), &key);
+    ///
+    /// let certificate_str = certificate.to_string();
+    /// ```
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        utils::bytes_to_string(&self.to_bytes())
+    }
+}
+
+impl FromStr for Certificate {
+    type Err = Error;
+    fn from_str(data: &str) -> Result<Certificate, Error> {
+        if data.len() % 2 != 0 || !data.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::Malformed("certificate needs to be hex"));
+        }
+        Certificate::from_bytes(&utils::bytes_from_str(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[42; 32])
+    }
+    #[test]
+    fn can_be_signed_and_verified() {
+        let (left, right) = (DNA::new(4, 4), DNA::new(4, 4));
+        let child = DNA::merge(left.clone(), right.clone(), false).unwrap();
+        let parents = vec![left.get_hash(), right.get_hash()];
+        let certificate = Certificate::sign(child.clone(), parents.clone(), &key());
+        let certificate_copy: Certificate = certificate.to_string().parse().unwrap();
+        assert!(certificate_copy.verify(&key().verifying_key()).is_ok());
+        assert_eq!(certificate_copy.dna.to_string(), child.to_string());
+        assert_eq!(certificate_copy.parents, parents);
+    }
+    #[test]
+    fn altered_dna_is_rejected() {
+        let mut certificate = Certificate::sign(DNA::new(4, 4), Vec::new(), &key());
+        certificate.dna = DNA::new(4, 4);
+        assert_eq!(
+            certificate.verify(&key().verifying_key()),
+            Err(Error::InvalidSignature)
+        );
+        let mut certificate = Certificate::sign(DNA::new(4, 4), Vec::new(), &key());
+        certificate.parents.push([0; 32]);
+        assert_eq!(
+            certificate.verify(&key().verifying_key()),
+            Err(Error::InvalidSignature)
+        );
+    }
+    #[test]
+    fn other_keys_are_rejected() {
+        let forger = SigningKey::from_bytes(&[1; 32]);
+        let mut certificate = Certificate::sign(DNA::new(4, 4), Vec::new(), &forger);
+        assert_eq!(
+            certificate.verify(&key().verifying_key()),
+            Err(Error::UnknownKey)
+        );
+        certificate.key_id = Certificate::key_id(&key().verifying_key());
+        assert_eq!(
+            certificate.verify(&key().verifying_key()),
+            Err(Error::InvalidSignature)
+        );
+    }
+    #[test]
+    fn truncated_bytes_are_rejected() {
+        let certificate = Certificate::sign(DNA::new(2, 2), vec![[3; 32]], &key());
+        let certificate_bytes = certificate.to_bytes();
+        for length in 0..certificate_bytes.len() {
+            assert!(Certificate::from_bytes(&certificate_bytes[..length]).is_err());
+        }
+        assert!("zz".parse::<Certificate>().is_err());
+    }
+}
@@ -0,0 +1,496 @@
+use crate::dna::DNA;
+use crate::error::Error;
+use crate::utils::{self, Reader};
+use std::collections::HashMap;
+
+/// Breeding settings for one part of a `CompositeDNA`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PartSettings {
+    // Probability of each gene and condition being mutated after merge
+    pub mutation_rate: f32,
+    // Weight of the part when comparing two composites
+    pub weight: f64,
+}
+
+impl Default for PartSettings {
+    fn default() -> PartSettings {
+        PartSettings {
+            mutation_rate: 0.0,
+            weight: 1.0,
+        }
+    }
+}
+
+/// A genome bundling named sub-DNAs, one for each generator rendering an asset
+#[derive(Clone, Default)]
+pub struct CompositeDNA {
+    // The named parts, kept in insertion order
+    parts: Vec<(String, DNA)>,
+}
+
+#[allow(clippy::inherent_to_string)]
+impl CompositeDNA {
+    /// Constructs a new empty `CompositeDNA`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::CompositeDNA;
+    ///
+    /// let composite = CompositeDNA::new();
+    /// ```
+    pub fn new() -> CompositeDNA {
+        CompositeDNA { parts: Vec::new() }
+    }
+    /// Add a named part, replacing any part with the same name. Names and the number of parts
+    /// need to fit in the u16 lengths of the composite string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    ///
+    /// let mut composite = CompositeDNA::new();
+    ///
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    /// composite.add_part("background", DNA::new(2, 16)).unwrap();
+    ///
+    /// assert!(composite.add_part(&"x".repeat(70000), DNA::new(2, 2)).is_err());
+    /// ```
+    pub fn add_part(&mut self, name: &str, dna: DNA) -> Result<(), Error> {
+        if name.len() > u16::MAX as usize {
+            return Err(Error::InvalidSetting(
+                "part name needs to be at most 65535 bytes",
+            ));
+        }
+        if let Some(part) = self.parts.iter_mut().find(|(n, _)| n == name) {
+            part.1 = dna;
+            return Ok(());
+        }
+        match self.parts.len() < u16::MAX as usize {
+            true => {
+                self.parts.push((String::from(name), dna));
+                Ok(())
+            }
+            false => Err(Error::InvalidSetting(
+                "composite DNA holds at most 65535 parts",
+            )),
+        }
+    }
+    /// Get every part with its name, in insertion order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    ///
+    /// let mut composite = CompositeDNA::new();
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// assert_eq!(composite.get_parts()[0].0, "body");
+    /// ```
+    pub fn get_parts(&self) -> &[(String, DNA)] {
+        &self.parts
+    }
+    /// Get the part with the given name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    ///
+    /// let mut composite = CompositeDNA::new();
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// let body = composite.get_part("body").unwrap();
+    /// ```
+    pub fn get_part(&self, name: &str) -> Option<&DNA> {
+        self.parts.iter().find(|(n, _)| n == name).map(|(_, d)| d)
+    }
+    /// Check if current composite DNA string is valid, including every part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    ///
+    /// let mut composite = CompositeDNA::new();
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// let is_valid = CompositeDNA::is_valid(composite.to_string());
+    ///
+    /// assert!(!CompositeDNA::is_valid(String::from("0000")));
+    /// ```
+    pub fn is_valid(composite_str: String) -> bool {
+        match composite_str.parse::<CompositeDNA>() {
+            Ok(composite) => {
+                composite.get_check_sum() == utils::u32_from_str(&composite_str[0..8])
+                    && composite
+                        .parts
+                        .iter()
+                        .all(|(_, d)| DNA::is_valid(d.to_string()))
+            }
+            Err(_) => false,
+        }
+    }
+    /// Merge two `CompositeDNA` part by part, parts need to have the same names and layouts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, PartSettings, DNA};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut composite1 = CompositeDNA::new();
+    /// composite1.add_part("body", DNA::new(8, 4)).unwrap();
+    /// let mut composite2 = CompositeDNA::new();
+    /// composite2.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// let mut settings = HashMap::new();
+    /// settings.insert(String::from("body"), PartSettings { mutation_rate: 0.1, weight: 1.0 });
+    ///
+    /// let merged = CompositeDNA::merge(composite1, composite2, &settings);
+    /// ```
+    pub fn merge(
+        left: CompositeDNA,
+        right: CompositeDNA,
+        settings: &HashMap<String, PartSettings>,
+    ) -> Option<CompositeDNA> {
+        if left.parts.len() != right.parts.len() {
+            return None;
+        }
+        left.parts
+            .into_iter()
+            .zip(right.parts)
+            .map(|((left_name, left_dna), (right_name, right_dna))| {
+                if left_name != right_name {
+                    return None;
+                }
+                let part_settings = get_settings(settings, &left_name);
+                let mut child = DNA::merge(left_dna, right_dna, false)?;
+                child.mutate(part_settings.mutation_rate);
+                Some((left_name, child))
+            })
+            .collect::<Option<Vec<(String, DNA)>>>()
+            .map(|parts| CompositeDNA { parts })
+    }
+    /// Compare two `CompositeDNA` similarity, the weighted average of each part similarity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut composite1 = CompositeDNA::new();
+    /// composite1.add_part("body", DNA::new(8, 4)).unwrap();
+    /// let composite2 = composite1.clone();
+    ///
+    /// let similarity = CompositeDNA::compare(&composite1, &composite2, &HashMap::new());
+    ///
+    /// assert_eq!(similarity, 1.0);
+    /// ```
+    pub fn compare(
+        left: &CompositeDNA,
+        right: &CompositeDNA,
+        settings: &HashMap<String, PartSettings>,
+    ) -> f64 {
+        let mut total_weight = 0.0;
+        let mut similarity = 0.0;
+        for (name, left_dna) in left.parts.iter() {
+            let weight = get_settings(settings, name).weight;
+            total_weight += weight;
+            if let Some(right_dna) = right.get_part(name) {
+                similarity += weight * DNA::compare(left_dna.clone(), right_dna.clone());
+            }
+        }
+        if total_weight == 0.0 {
+            return 0.0;
+        }
+        similarity / total_weight
+    }
+    /// Mutate every part with its own mutation rate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, PartSettings, DNA};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut composite = CompositeDNA::new();
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// let mut settings = HashMap::new();
+    /// settings.insert(String::from("body"), PartSettings { mutation_rate: 0.5, weight: 1.0 });
+    ///
+    /// composite.mutate(&settings);
+    /// ```
+    pub fn mutate(&mut self, settings: &HashMap<String, PartSettings>) {
+        for (name, dna) in self.parts.iter_mut() {
+            dna.mutate(get_settings(settings, name).mutation_rate);
+        }
+    }
+    /// Get composite checksum, the CRC-32 of the string after the checksum, so reordered or
+    /// swapped parts are detected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    ///
+    /// let mut composite = CompositeDNA::new();
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// let check_sum = composite.get_check_sum();
+    /// ```
+    pub fn get_check_sum(&self) -> u32 {
+        utils::crc32(self.body().as_bytes())
+    }
+    /// Convert composite DNA to string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{CompositeDNA, DNA};
+    ///
+    /// let mut composite = CompositeDNA::new();
+    /// composite.add_part("body", DNA::new(8, 4)).unwrap();
+    ///
+    /// let composite_str = composite.to_string();
+    /// ```
+    pub fn to_string(&self) -> String {
+        let body = self.body();
+        format!(
+            "{}{}",
+            utils::u32_to_string(utils::crc32(body.as_bytes())),
+            body
+        )
+    }
+    /// The string without the checksum, part count followed by each part
+    fn body(&self) -> String {
+        let count_hex: String = utils::u16_to_string(self.parts.len() as u16);
+
+        format!(
+            "{}{}",
+            count_hex,
+            self.parts
+                .iter()
+                .map(|(name, dna)| {
+                    let dna_str = dna.to_string();
+                    format!(
+                        "{}{}{}{}",
+                        utils::u16_to_string(name.len() as u16),
+                        utils::bytes_to_string(name.as_bytes()),
+                        utils::u32_to_string(dna_str.len() as u32),
+                        dna_str
+                    )
+                })
+                .collect::<String>()
+        )
+    }
+}
+
+fn get_settings(settings: &HashMap<String, PartSettings>, name: &str) -> PartSettings {
+    settings.get(name).copied().unwrap_or_default()
+}
+
+/// Convert composite DNA to string
+///
+/// # Examples
+///
+/// ```
+/// use genome::{CompositeDNA, DNA};
+///
+/// let mut composite = CompositeDNA::new();
+/// composite.add_part("body", DNA::new(8, 4)).unwrap();
+///
+/// let composite_str = String::from(composite);
+/// ```
+impl std::convert::From<CompositeDNA> for String {
+    fn from(composite: CompositeDNA) -> String {
+        composite.to_string()
+    }
+}
+
+/// Convert string to composite DNA
+///
+/// # Examples
+///
+/// ```
+/// use genome::{CompositeDNA, DNA};
+///
+/// let mut composite = CompositeDNA::new();
+/// composite.add_part("body", DNA::new(8, 4)).unwrap();
+/// let composite_str = composite.to_string();
+///
+/// let composite_copy = CompositeDNA::from(composite_str);
+/// ```
+impl std::convert::From<String> for CompositeDNA {
+    fn from(composite: String) -> CompositeDNA {
+        composite.parse().expect("Invalid composite DNA string")
+    }
+}
+
+/// Parse string to composite DNA, the checksum is not checked
+///
+/// # Examples
+///
+/// ```
+/// use genome::{CompositeDNA, DNA};
+///
+/// let mut composite = CompositeDNA::new();
+/// composite.add_part("body", DNA::new(8, 4)).unwrap();
+/// let composite_str = composite.to_string();
+///
+/// let composite_copy: CompositeDNA = composite_str.parse().unwrap();
+///
+/// assert!("not a composite".parse::<CompositeDNA>().is_err());
+/// ```
+impl std::str::FromStr for CompositeDNA {
+    type Err = Error;
+
+    fn from_str(composite: &str) -> Result<CompositeDNA, Error> {
+        if !composite.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::Malformed("composite DNA string needs to be hex"));
+        }
+        let mut reader = Reader {
+            data: composite.as_bytes(),
+        };
+        // Ignore the first 8 char for checksum
+        read_hex(&mut reader, 8)?;
+        let count = utils::u16_from_str(read_hex(&mut reader, 4)?);
+        let parts = (0..count)
+            .map(|_| {
+                let name_length = utils::u16_from_str(read_hex(&mut reader, 4)?) as usize;
+                let name = String::from_utf8(utils::bytes_from_str(read_hex(
+                    &mut reader,
+                    name_length * 2,
+                )?))
+                .map_err(|_| Error::Malformed("part name needs to be UTF-8"))?;
+                let dna_length = utils::u32_from_str(read_hex(&mut reader, 8)?) as usize;
+                let dna = read_hex(&mut reader, dna_length)?.parse::<DNA>()?;
+                Ok((name, dna))
+            })
+            .collect::<Result<Vec<(String, DNA)>, Error>>()?;
+        if !reader.data.is_empty() {
+            return Err(Error::Malformed("trailing data after composite DNA"));
+        }
+        Ok(CompositeDNA { parts })
+    }
+}
+
+/// Read `length` hex chars
+fn read_hex<'a>(reader: &mut Reader<'a>, length: usize) -> Result<&'a str, Error> {
+    std::str::from_utf8(reader.read_bytes(length)?)
+        .map_err(|_| Error::Malformed("composite DNA string needs to be hex"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn new_composite() -> CompositeDNA {
+        let mut composite = CompositeDNA::new();
+        composite.add_part("body", DNA::new(8, 4)).unwrap();
+        composite.add_part("background", DNA::new(2, 16)).unwrap();
+        composite
+            .add_part("accessory", DNA::new_conditional(4, 2, &[6]))
+            .unwrap();
+        composite
+    }
+    #[test]
+    fn can_be_converted_and_back() {
+        let composite = new_composite();
+        let composite_str = composite.to_string();
+        let restored = CompositeDNA::from(composite_str.clone());
+        assert_eq!(restored.parts.len(), 3);
+        assert_eq!(restored.get_part("background").unwrap().gene_size, 16);
+        assert_eq!(restored.to_string(), composite_str);
+        assert!(CompositeDNA::is_valid(composite_str));
+    }
+    #[test]
+    fn swapped_parts_are_invalid() {
+        let mut composite = CompositeDNA::new();
+        let body = DNA::new(4, 4);
+        let tail = DNA::new(4, 4);
+        composite.add_part("body", body.clone()).unwrap();
+        composite.add_part("tail", tail.clone()).unwrap();
+        let composite_str = composite.to_string();
+        assert!(CompositeDNA::is_valid(composite_str.clone()));
+        let mut swapped = CompositeDNA::new();
+        swapped.add_part("body", tail).unwrap();
+        swapped.add_part("tail", body).unwrap();
+        let swapped_str = format!("{}{}", &composite_str[0..8], &swapped.to_string()[8..]);
+        assert!(!CompositeDNA::is_valid(swapped_str));
+    }
+    #[test]
+    fn invalid_strings_are_rejected() {
+        let composite_str = new_composite().to_string();
+        assert!(!CompositeDNA::is_valid(String::new()));
+        assert!(!CompositeDNA::is_valid(String::from("0000000000zz")));
+        let truncated = &composite_str[..composite_str.len() - 3];
+        assert!(truncated.parse::<CompositeDNA>().is_err());
+        assert!(!CompositeDNA::is_valid(truncated.to_string()));
+        assert!(format!("{}00", composite_str)
+            .parse::<CompositeDNA>()
+            .is_err());
+    }
+    #[test]
+    fn add_part_replaces() {
+        let mut composite = new_composite();
+        composite.add_part("body", DNA::new(1, 1)).unwrap();
+        assert_eq!(composite.parts.len(), 3);
+        assert_eq!(composite.get_part("body").unwrap().pool_size, 1);
+    }
+    #[test]
+    fn part_limits_are_checked() {
+        let mut composite = CompositeDNA::new();
+        assert!(composite
+            .add_part(&"x".repeat(u16::MAX as usize + 1), DNA::new(1, 1))
+            .is_err());
+        composite
+            .add_part(&"x".repeat(u16::MAX as usize), DNA::new(1, 1))
+            .unwrap();
+        let dna = DNA::new(1, 1);
+        composite
+            .parts
+            .extend((2..u16::MAX).map(|i| (i.to_string(), dna.clone())));
+        composite.add_part("1", dna.clone()).unwrap();
+        assert!(composite.add_part("full", DNA::new(1, 1)).is_err());
+        composite.add_part("1", DNA::new(2, 1)).unwrap();
+        assert_eq!(CompositeDNA::from(composite.to_string()).parts.len(), 65535);
+    }
+    #[test]
+    fn merges_part_by_part() {
+        let left = new_composite();
+        let right = new_composite();
+        let child = CompositeDNA::merge(left.clone(), right.clone(), &HashMap::new()).unwrap();
+        for (name, dna) in child.parts.iter() {
+            let left_similarity = DNA::compare(dna.clone(), left.get_part(name).unwrap().clone());
+            let right_similarity = DNA::compare(dna.clone(), right.get_part(name).unwrap().clone());
+            assert_eq!(left_similarity + right_similarity, 1.0);
+        }
+    }
+    #[test]
+    fn cannot_be_merged() {
+        let left = new_composite();
+        let mut right = new_composite();
+        right.add_part("body", DNA::new(4, 4)).unwrap();
+        assert!(CompositeDNA::merge(left, right, &HashMap::new()).is_none());
+    }
+    #[test]
+    fn compare_uses_weights() {
+        let left = new_composite();
+        let mut right = left.clone();
+        right.add_part("body", DNA::new(8, 4)).unwrap();
+        let mut settings = HashMap::new();
+        settings.insert(
+            String::from("body"),
+            PartSettings {
+                mutation_rate: 0.0,
+                weight: 0.0,
+            },
+        );
+        assert_eq!(CompositeDNA::compare(&left, &right, &settings), 1.0);
+        assert!(CompositeDNA::compare(&left, &right, &HashMap::new()) < 1.0);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::uniform;
+use crate::utils;
+use rand::prelude::*;
+
+/// A categorical conditioning input, the class label fed to a conditional GAN
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Condition {
+    // The number of classes this condition can take
+    pub num_classes: u16,
+    // The selected class, always less than `num_classes`
+    pub class_id: u16,
+}
+
+#[allow(clippy::inherent_to_string)]
+impl Condition {
+    /// Create a new condition with a random class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let condition = Condition::new(10);
+    /// ```
+    pub fn new(num_classes: u16) -> Condition {
+        if num_classes < 1 {
+            panic!("Classes needs to be more than 0");
+        }
+        Condition {
+            num_classes,
+            class_id: thread_rng().gen_range(0, num_classes),
+        }
+    }
+    /// Create a new condition with the given class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let condition = Condition::from_class(10, 3);
+    /// ```
+    pub fn from_class(num_classes: u16, class_id: u16) -> Condition {
+        if class_id >= num_classes {
+            panic!("Class needs to be less than the number of classes");
+        }
+        Condition {
+            num_classes,
+            class_id,
+        }
+    }
+    /// Get the one-hot encoding of the class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let condition = Condition::from_class(3, 1);
+    ///
+    /// assert_eq!(condition.to_one_hot(), vec![0.0, 1.0, 0.0]);
+    /// ```
+    pub fn to_one_hot(&self) -> Vec<f32> {
+        (0..self.num_classes)
+            .map(|i| if i == self.class_id { 1.0 } else { 0.0 })
+            .collect()
+    }
+    /// Get the index of the class in an embedding table
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let condition = Condition::from_class(3, 1);
+    ///
+    /// assert_eq!(condition.embedding_index(), 1);
+    /// ```
+    pub fn embedding_index(&self) -> usize {
+        self.class_id as usize
+    }
+    /// Check if two conditions hold the same set of classes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let condition1 = Condition::new(3);
+    /// let condition2 = Condition::new(3);
+    ///
+    /// assert!(Condition::is_compatible(&condition1, &condition2));
+    /// ```
+    pub fn is_compatible(left: &Condition, right: &Condition) -> bool {
+        left.num_classes == right.num_classes
+    }
+    /// Mutate the condition by picking a new random class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let mut condition = Condition::new(3);
+    ///
+    /// condition.mutate();
+    /// ```
+    pub fn mutate(&mut self) {
+        self.mutate_with_rng(&mut thread_rng());
+    }
+    /// Mutate the condition by picking a new class drawn from `rng`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ChaCha20Rng, Condition};
+    /// use rand::SeedableRng;
+    ///
+    /// let mut condition = Condition::new(3);
+    ///
+    /// condition.mutate_with_rng(&mut ChaCha20Rng::seed_from_u64(7));
+    /// ```
+    pub fn mutate_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.class_id = uniform::range(rng, 0, self.num_classes as usize) as u16;
+    }
+    /// Convert condition to big endian bytes
+    pub(crate) fn to_bytes(self) -> [u8; 4] {
+        let num_classes = self.num_classes.to_be_bytes();
+        let class_id = self.class_id.to_be_bytes();
+        [num_classes[0], num_classes[1], class_id[0], class_id[1]]
+    }
+    /// Convert condition to string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Condition;
+    ///
+    /// let condition = Condition::from_class(3, 1);
+    ///
+    /// assert_eq!(condition.to_string(), "00030001");
+    /// ```
+    pub fn to_string(&self) -> String {
+        format!(
+            "{}{}",
+            utils::u16_to_string(self.num_classes),
+            utils::u16_to_string(self.class_id)
+        )
+    }
+}
+
+/// Convert condition to string
+///
+/// # Examples
+///
+/// ```
+/// use genome::Condition;
+///
+/// let condition = Condition::new(3);
+///
+/// let condition_str = String::from(condition);
+/// ```
+impl std::convert::From<Condition> for String {
+    fn from(condition: Condition) -> String {
+        condition.to_string()
+    }
+}
+
+/// Convert string to condition
+///
+/// # Examples
+///
+/// ```
+/// use genome::Condition;
+///
+/// let condition = Condition::new(3);
+/// let condition_str = condition.to_string();
+///
+/// let condition_copy = Condition::from(condition_str);
+/// ```
+impl std::convert::From<String> for Condition {
+    fn from(condition: String) -> Condition {
+        Condition {
+            num_classes: utils::u16_from_str(&condition[0..4]),
+            class_id: utils::u16_from_str(&condition[4..8]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn can_be_converted_and_back() {
+        let condition = Condition::new(12);
+        let string_value: String = condition.into();
+        let restored: Condition = string_value.into();
+        assert_eq!(condition, restored);
+    }
+    #[test]
+    fn one_hot_has_single_class() {
+        let condition = Condition::new(12);
+        let one_hot = condition.to_one_hot();
+        assert_eq!(one_hot.len(), 12);
+        assert_eq!(one_hot.iter().sum::<f32>(), 1.0);
+        assert_eq!(one_hot[condition.embedding_index()], 1.0);
+    }
+    #[test]
+    #[should_panic]
+    fn class_out_of_range() {
+        Condition::from_class(2, 2);
+    }
+    #[test]
+    fn mutate_stays_in_range() {
+        let mut condition = Condition::new(3);
+        for _ in 0..64 {
+            condition.mutate();
+            assert!(condition.class_id < 3);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::dna::{LatentMode, DNA};
+use crate::error::Error;
+use std::str::FromStr;
+
+/// A named direction in the markers only latent, such as "age" or "smile"
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Direction {
+    pub name: String,
+    pub vector: Vec<f32>,
+}
+
+impl Direction {
+    /// Load directions from text, one per line as the name followed by its values separated
+    /// by whitespace. Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Direction;
+    ///
+    /// let directions = Direction::load("# age and smile\nage 0.5 0 0 0.5\nsmile 0 1 0 0\n").unwrap();
+    ///
+    /// assert_eq!(directions[1].name, "smile");
+    /// ```
+    pub fn load(data: &str) -> Result<Vec<Direction>, Error> {
+        data.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Direction::from_str)
+            .collect()
+    }
+    /// Fit a direction from DNA with and without an attribute, the difference of their mean
+    /// latents scaled to unit length. `None` when a set is empty, the layouts differ or the
+    /// means are the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Direction, DNA};
+    ///
+    /// let smiling = (0..8).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    /// let neutral = (0..8).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    ///
+    /// let smile = Direction::fit("smile", &smiling, &neutral).unwrap();
+    ///
+    /// assert_eq!(smile.vector.len(), 4);
+    /// ```
+    pub fn fit(name: &str, positive: &[DNA], negative: &[DNA]) -> Option<Direction> {
+        let positive_mean = DNA::mean_latent(positive)?;
+        let negative_mean = DNA::mean_latent(negative)?;
+        if positive[0].get_layout() != negative[0].get_layout() {
+            return None;
+        }
+        let vector = positive_mean
+            .iter()
+            .zip(negative_mean.iter())
+            .map(|(p, n)| p - n)
+            .collect::<Vec<f32>>();
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            return None;
+        }
+        Some(Direction {
+            name: String::from(name),
+            vector: vector.iter().map(|v| v / norm).collect(),
+        })
+    }
+    /// Move the markers of a DNA along the direction by `strength`, only in `genes` when set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Direction, Influence, DNA};
+    ///
+    /// let dna1 = DNA::from_latent_vec(&[1.0, 0.0, 0.0, 2.0], 2, Influence::Default(0.5)).unwrap();
+    /// let age: Direction = "age 1 0 0 1".parse().unwrap();
+    ///
+    /// let dna2 = age.apply(&dna1, 0.5, Some(&[1])).unwrap();
+    ///
+    /// assert_eq!(dna2.to_latent_vec(), vec![0.5, 1.0, 0.0, 0.5, 0.0, 2.5]);
+    /// ```
+    pub fn apply(&self, dna: &DNA, strength: f32, genes: Option<&[usize]>) -> Result<DNA, Error> {
+        if dna.gene_size == 0 {
+            return Err(Error::InvalidSetting("gene size needs to be more than 0"));
+        }
+        let dim = dna.latent_dim(LatentMode::Markers);
+        if self.vector.len() != dim {
+            return Err(Error::LengthMismatch {
+                expected: dim,
+                found: self.vector.len(),
+            });
+        }
+        if !strength.is_finite() {
+            return Err(Error::InvalidSetting(
+                "strength needs to be a finite number",
+            ));
+        }
+        if let Some(genes) = genes {
+            if genes.iter().any(|g| *g >= dna.genes.len()) {
+                return Err(Error::InvalidSetting("gene index out of range"));
+            }
+        }
+        let mut edited = dna.clone();
+        let chunks = self.vector.chunks(dna.gene_size as usize);
+        for (i, (gene, chunk)) in edited.genes.iter_mut().zip(chunks).enumerate() {
+            if genes.map_or(true, |genes| genes.contains(&i)) {
+                for (marker, value) in gene.markers.iter_mut().skip(1).zip(chunk.iter()) {
+                    marker.value += strength * value;
+                }
+            }
+        }
+        Ok(edited)
+    }
+    /// Convert direction to string, the same form `Direction::load` reads
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Direction;
+    ///
+    /// let age = Direction {
+    ///     name: String::from("age"),
+    ///     vector: vec![0.5, -1.0],
+    /// };
+    ///
+    /// assert_eq!(age.to_string(), "age 0.5 -1");
+    /// ```
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        std::iter::once(self.name.clone())
+            .chain(self.vector.iter().map(|v| v.to_string()))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+    fn from_str(data: &str) -> Result<Direction, Error> {
+        let mut parts = data.split_whitespace();
+        let name = parts
+            .next()
+            .ok_or(Error::Malformed("missing direction name"))?;
+        let vector = parts
+            .map(|v| {
+                v.parse::<f32>()
+                    .map_err(|_| Error::Malformed("invalid direction value"))
+            })
+            .collect::<Result<Vec<f32>, Error>>()?;
+        if vector.is_empty() || vector.iter().any(|v| !v.is_finite()) {
+            return Err(Error::Malformed("direction needs finite values"));
+        }
+        Ok(Direction {
+            name: String::from(name),
+            vector,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dna::Influence;
+    #[test]
+    fn can_be_converted_and_back() {
+        let direction = Direction {
+            name: String::from("smile"),
+            vector: vec![0.1, -2.5, 1e-7, 3.0],
+        };
+        assert_eq!(
+            direction.to_string().parse::<Direction>().unwrap(),
+            direction
+        );
+        assert!(Direction::load("smile").is_err());
+        assert!(Direction::load("smile 1 x").is_err());
+        assert!(Direction::load("smile 1 NaN").is_err());
+    }
+    #[test]
+    fn fit_finds_direction() {
+        let positive = [
+            DNA::from_latent_vec(&[1.0, 1.0, 1.0, 3.0], 1, Influence::Included).unwrap(),
+            DNA::from_latent_vec(&[1.0, 1.0, 1.0, 5.0], 1, Influence::Included).unwrap(),
+        ];
+        let negative =
+            [DNA::from_latent_vec(&[0.0, 1.0, 0.0, 1.0], 1, Influence::Included).unwrap()];
+        let direction = Direction::fit("up", &positive, &negative).unwrap();
+        assert_eq!(direction.vector, vec![0.0, 1.0]);
+        let dna = direction.apply(&negative[0], 3.0, None).unwrap();
+        assert_eq!(dna.to_latent_vec(), vec![0.0, 1.0, 0.0, 4.0]);
+        assert!(Direction::fit("up", &positive, &positive).is_none());
+        assert!(Direction::fit("up", &positive, &[DNA::new(1, 2)]).is_none());
+    }
+    #[test]
+    fn apply_is_checked() {
+        let dna = DNA::new(2, 2);
+        let direction = Direction {
+            name: String::from("age"),
+            vector: vec![1.0; 3],
+        };
+        assert_eq!(
+            direction.apply(&dna, 1.0, None).err(),
+            Some(Error::LengthMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+        let direction = Direction {
+            name: String::from("age"),
+            vector: vec![1.0; 4],
+        };
+        assert!(direction.apply(&dna, 1.0, Some(&[2])).is_err());
+        assert!(direction.apply(&dna, f32::INFINITY, None).is_err());
+        let edited = direction.apply(&dna, 0.0, None).unwrap();
+        assert_eq!(edited.to_latent_vec(), dna.to_latent_vec());
+        let mut empty = DNA::new(2, 1);
+        empty.gene_size = 0;
+        let direction = Direction {
+            name: String::from("age"),
+            vector: Vec::new(),
+        };
+        assert_eq!(
+            direction.apply(&empty, 1.0, None).err(),
+            Some(Error::InvalidSetting("gene size needs to be more than 0"))
+        );
+    }
+}
@@ -1,52 +1,1117 @@
+pub(crate) mod binary;
+mod derive;
+mod interpolate;
+mod project;
+mod section;
+use crate::base58;
+use crate::bech32;
+use crate::condition::Condition;
+use crate::encoding::Encoding;
+use crate::error::Error;
 use crate::gene::Gene;
+use crate::mixing::Mixing;
+use crate::model::Model;
+use crate::truncation::Truncation;
+use crate::uniform;
 use crate::utils;
 use rand::prelude::*;
+use rand_chacha::ChaCha20Rng;
+use section::Section;
+use sha2::{Digest, Sha256};
 
+/// The shape of a `DNA`, number of genes and markers per gene
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub pool_size: u16,
+    pub gene_size: u16,
+}
+
+/// Where the influence markers come from when building a `DNA` from a latent vector
+#[derive(Clone, Debug, PartialEq)]
+pub enum Influence {
+    /// The latent holds each influence before the markers of its gene, as `to_latent_vec` writes it
+    Included,
+    /// The latent holds only markers, with one influence per gene given here
+    Separate(Vec<f32>),
+    /// The latent holds only markers, every influence is set to this value
+    Default(f32),
+}
+
+/// How `to_latent_vec_with` lays out the markers of a `DNA`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LatentMode {
+    /// Each gene is its influence followed by its markers, as `to_latent_vec` writes it
+    Included,
+    /// Only the markers of each gene, without influence
+    Markers,
+    /// The markers of every gene, followed by the influence of every gene
+    Appended,
+    /// The markers of each gene multiplied by its influence
+    Scaled,
+}
+
+/// How `DNA::interpolate` moves between two `DNA`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Interpolation {
+    /// Straight line between the latent vectors
+    Linear,
+    /// Along the arc between the latent vectors, keeps the norm expected by most GANs
+    Spherical,
+}
+
+/// How `DNA::project` maps the markers of a `DNA` onto another layout
+#[derive(Clone, Debug, PartialEq)]
+pub enum Projection {
+    /// Keep every marker at its gene and position, cutting what does not fit and padding with
+    /// zeros. Projecting a padded `DNA` back gives the original.
+    Resize,
+    /// Split the markers only latent into genes of the new size, cutting or padding its end with
+    /// zeros. Lossless when the latent dimension stays the same.
+    Rechunk,
+    /// Multiply the markers only latent by a row major matrix with one row per new marker,
+    /// reversed by projecting with its inverse.
+    Matrix(Vec<f32>),
+}
+
+#[derive(Clone)]
 pub struct DNA {
     // The pool size, number of genes
     pub pool_size: u16,
     // The amount of marker each gene has, each marker is f32
     pub gene_size: u16,
+    // How markers are written in the DNA string
+    encoding: Encoding,
     // The genes for this DNA sequence
     pub genes: Vec<Gene>,
+    // The conditioning inputs, exported separately from the latent vector
+    conditions: Vec<Condition>,
+    // The generator this DNA is made for, if known
+    pub model: Option<Model>,
 }
 
-impl DNA {
-    /// Constructs a new `DNA`.
+impl DNA {
+    /// Constructs a new `DNA`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna = DNA::new(2, 2);
+    /// ```
+    pub fn new(pool_size: u16, gene_size: u16) -> DNA {
+        DNA::new_with_rng(pool_size, gene_size, &mut thread_rng())
+    }
+    /// Constructs a new `DNA`, drawing markers from `rng`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ChaCha20Rng, DNA};
+    /// use rand::SeedableRng;
+    ///
+    /// let dna = DNA::new_with_rng(2, 2, &mut ChaCha20Rng::seed_from_u64(7));
+    /// ```
+    pub fn new_with_rng<R: Rng + ?Sized>(pool_size: u16, gene_size: u16, rng: &mut R) -> DNA {
+        DNA {
+            pool_size,
+            gene_size,
+            encoding: Encoding::F32,
+            genes: (0..pool_size)
+                .map(|_| Gene::new_with_rng(gene_size, rng))
+                .collect(),
+            conditions: Vec::new(),
+            model: None,
+        }
+    }
+    /// Constructs a new `DNA` with `truncation` applied to its markers
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Truncation, DNA};
+    ///
+    /// let truncation = Truncation {
+    ///     psi: 0.7,
+    ///     ..Truncation::default()
+    /// };
+    ///
+    /// let dna = DNA::new_truncated(2, 2, &truncation).unwrap();
+    /// ```
+    pub fn new_truncated(
+        pool_size: u16,
+        gene_size: u16,
+        truncation: &Truncation,
+    ) -> Result<DNA, Error> {
+        let mut dna = DNA::new(pool_size, gene_size);
+        truncation.apply(&mut dna)?;
+        Ok(dna)
+    }
+    /// Constructs a `DNA` from a seed, the same seed always gives the same DNA.
+    ///
+    /// The generator is ChaCha20 and markers use the crate's own Box-Muller, so the DNA of a
+    /// seed does not depend on the versions of `rand` or `rand_distr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Layout, DNA};
+    ///
+    /// let layout = Layout {
+    ///     pool_size: 2,
+    ///     gene_size: 2,
+    /// };
+    ///
+    /// let dna = DNA::from_seed(42, layout);
+    ///
+    /// assert_eq!(dna.to_string(), DNA::from_seed(42, layout).to_string());
+    /// ```
+    pub fn from_seed(seed: u64, layout: Layout) -> DNA {
+        DNA::new_with_rng(
+            layout.pool_size,
+            layout.gene_size,
+            &mut ChaCha20Rng::seed_from_u64(seed),
+        )
+    }
+    /// Constructs a `DNA` derived from `input`, the same input always gives the same DNA.
+    ///
+    /// Markers come from a versioned SHA-256 counter stream mapped to standard normals with
+    /// Box-Muller, computed without the platform math library. The DNA of an input will not
+    /// change across platforms or crate versions. The gene size needs to be more than 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Layout, DNA};
+    ///
+    /// let layout = Layout {
+    ///     pool_size: 2,
+    ///     gene_size: 2,
+    /// };
+    ///
+    /// let dna = DNA::derive(b"token 42", layout);
+    ///
+    /// assert_eq!(dna.to_string(), DNA::derive(b"token 42", layout).to_string());
+    /// ```
+    pub fn derive(input: &[u8], layout: Layout) -> DNA {
+        if layout.gene_size < 1 {
+            panic!("Markers needs to be more than 0");
+        }
+        let mut normals = derive::standard_normals(input);
+        DNA::from_genes(
+            layout.gene_size,
+            (0..layout.pool_size)
+                .map(|_| {
+                    Gene::from(
+                        normals
+                            .by_ref()
+                            .take(layout.gene_size as usize + 1)
+                            .collect::<Vec<f32>>(),
+                    )
+                })
+                .collect(),
+        )
+    }
+    /// Constructs a new `DNA` with a random class for each conditioning input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna = DNA::new_conditional(2, 2, &[10, 4]);
+    /// ```
+    pub fn new_conditional(pool_size: u16, gene_size: u16, num_classes: &[u16]) -> DNA {
+        if num_classes.len() > section::MAX_CONDITIONS {
+            panic!("Conditions needs to fit in a section");
+        }
+        let mut dna = DNA::new(pool_size, gene_size);
+        dna.conditions = num_classes.iter().map(|n| Condition::new(*n)).collect();
+        dna
+    }
+    /// Constructs a `DNA` from existing genes, with default encoding and no conditions.
+    pub(crate) fn from_genes(gene_size: u16, genes: Vec<Gene>) -> DNA {
+        DNA {
+            pool_size: genes.len() as u16,
+            gene_size,
+            encoding: Encoding::F32,
+            genes,
+            conditions: Vec::new(),
+            model: None,
+        }
+    }
+    /// Check if current DNA string is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    /// let dna = DNA::new(2, 2);
+    ///
+    /// let is_valid = DNA::is_valid(dna.to_string());
+    /// ```
+    pub fn is_valid(dna_str: String) -> bool {
+        match dna_str.parse::<DNA>() {
+            Ok(dna) => dna.get_check_sum() == utils::f32_from_str(&dna_str[0..8]),
+            Err(_) => false,
+        }
+    }
+    /// Merge two `DNA` into one, each condition is inherited from either parent. Parents made
+    /// for different models are refused, the child keeps the model of either parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    /// let dna2 = DNA::new(2, 2);
+    ///
+    /// let merged = DNA::merge(dna1, dna2, false);
+    /// ```
+    pub fn merge(left_dna: DNA, right_dna: DNA, mutate: bool) -> Option<DNA> {
+        DNA::merge_with_rng(left_dna, right_dna, mutate, &mut thread_rng())
+    }
+    /// Merge two `DNA` into one, drawing every choice from `rng` so the same seed gives the
+    /// same child. Choices use the crate's own sampling, not the version dependent one of `rand`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ChaCha20Rng, DNA};
+    /// use rand::SeedableRng;
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    /// let dna2 = DNA::new(2, 2);
+    ///
+    /// let merged = DNA::merge_with_rng(dna1, dna2, true, &mut ChaCha20Rng::seed_from_u64(7));
+    /// ```
+    pub fn merge_with_rng<R: Rng + ?Sized>(
+        left_dna: DNA,
+        right_dna: DNA,
+        mutate: bool,
+        rng: &mut R,
+    ) -> Option<DNA> {
+        let model = DNA::merge_model(&left_dna, &right_dna)?;
+        let conditions = DNA::merge_conditions(&left_dna, &right_dna, mutate, rng)?;
+        match (left_dna.pool_size == right_dna.pool_size)
+            && (left_dna.gene_size == right_dna.gene_size)
+        {
+            true => Some(DNA {
+                pool_size: left_dna.pool_size,
+                gene_size: left_dna.gene_size,
+                encoding: left_dna.encoding,
+                genes: (0..left_dna.pool_size)
+                    .map(|i| {
+                        let mut gene = if uniform::uniform_f32(rng) >= 0.5 {
+                            left_dna.genes[i as usize].clone()
+                        } else {
+                            right_dna.genes[i as usize].clone()
+                        };
+                        if mutate && uniform::uniform_f32(rng) >= 0.9 {
+                            gene.mutate_with_rng(rng);
+                        }
+                        gene
+                    })
+                    .collect(),
+                conditions,
+                model,
+            }),
+            false => None,
+        }
+    }
+    /// Get the model of a child, `None` when the parents are made for different models
+    fn merge_model(left_dna: &DNA, right_dna: &DNA) -> Option<Option<Model>> {
+        match (&left_dna.model, &right_dna.model) {
+            (Some(left), Some(right)) if left != right => None,
+            (left, right) => Some(left.clone().or_else(|| right.clone())),
+        }
+    }
+    fn merge_conditions<R: Rng + ?Sized>(
+        left_dna: &DNA,
+        right_dna: &DNA,
+        mutate: bool,
+        rng: &mut R,
+    ) -> Option<Vec<Condition>> {
+        if left_dna.conditions.len() != right_dna.conditions.len() {
+            return None;
+        }
+        left_dna
+            .conditions
+            .iter()
+            .zip(right_dna.conditions.iter())
+            .map(|(left, right)| {
+                if !Condition::is_compatible(left, right) {
+                    return None;
+                }
+                let mut condition = if uniform::uniform_f32(rng) >= 0.5 {
+                    *left
+                } else {
+                    *right
+                };
+                if mutate && uniform::uniform_f32(rng) >= 0.9 {
+                    condition.mutate_with_rng(rng);
+                }
+                Some(condition)
+            })
+            .collect()
+    }
+    /// Interpolate between two `DNA` with the same layout, `t` of 0 is `left_dna` and 1 is
+    /// `right_dna`.
+    ///
+    /// Markers are interpolated by `method` over the whole latent, influences are always
+    /// interpolated linearly with the same `t`. Encoding and conditions come from the closer
+    /// parent, parents made for different models are refused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Interpolation, DNA};
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    /// let dna2 = DNA::new(2, 2);
+    ///
+    /// let middle = DNA::interpolate(&dna1, &dna2, 0.5, Interpolation::Spherical).unwrap();
+    ///
+    /// let dna_str = middle.to_string();
+    /// ```
+    pub fn interpolate(
+        left_dna: &DNA,
+        right_dna: &DNA,
+        t: f32,
+        method: Interpolation,
+    ) -> Option<DNA> {
+        if !DNA::is_mixable(left_dna, right_dna) {
+            return None;
+        }
+        let model = DNA::merge_model(left_dna, right_dna)?;
+        Some(DNA::blend(left_dna, right_dna, t, method, model))
+    }
+    /// Interpolate two `DNA` already checked by `is_mixable`, `model` is their merged model
+    fn blend(
+        left_dna: &DNA,
+        right_dna: &DNA,
+        t: f32,
+        method: Interpolation,
+        model: Option<Model>,
+    ) -> DNA {
+        let markers = (
+            left_dna.to_latent_vec_with(LatentMode::Markers),
+            right_dna.to_latent_vec_with(LatentMode::Markers),
+        );
+        let influences = (
+            left_dna
+                .genes
+                .iter()
+                .map(|g| g.get_influence())
+                .collect::<Vec<f32>>(),
+            right_dna
+                .genes
+                .iter()
+                .map(|g| g.get_influence())
+                .collect::<Vec<f32>>(),
+        );
+        let markers = match method {
+            Interpolation::Linear => interpolate::lerp(&markers.0, &markers.1, t),
+            Interpolation::Spherical => interpolate::slerp(&markers.0, &markers.1, t),
+        };
+        let influences = interpolate::lerp(&influences.0, &influences.1, t);
+        let gene_size = left_dna.gene_size as usize;
+        let genes = influences
+            .iter()
+            .enumerate()
+            .map(|(i, influence)| {
+                let gene_markers = &markers[i * gene_size..(i + 1) * gene_size];
+                Gene::from([&[*influence], gene_markers].concat())
+            })
+            .collect();
+        let closer = if t < 0.5 { left_dna } else { right_dna };
+        let mut dna = DNA::from_genes(left_dna.gene_size, genes);
+        dna.encoding = closer.encoding;
+        dna.conditions = closer.conditions.clone();
+        dna.model = model;
+        dna
+    }
+    /// Get `frames` evenly spaced `DNA` from `left_dna` to `right_dna`, both included
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Interpolation, DNA};
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    /// let dna2 = DNA::new(2, 2);
+    ///
+    /// let frames = DNA::morph(&dna1, &dna2, 5, Interpolation::Linear)
+    ///     .unwrap()
+    ///     .collect::<Vec<DNA>>();
+    ///
+    /// assert_eq!(frames.len(), 5);
+    /// assert_eq!(frames[4].to_string(), dna2.to_string());
+    /// ```
+    pub fn morph<'a>(
+        left_dna: &'a DNA,
+        right_dna: &'a DNA,
+        frames: usize,
+        method: Interpolation,
+    ) -> Option<impl Iterator<Item = DNA> + 'a> {
+        if !DNA::is_mixable(left_dna, right_dna) {
+            return None;
+        }
+        let model = DNA::merge_model(left_dna, right_dna)?;
+        let last = frames.saturating_sub(1).max(1) as f32;
+        Some(
+            (0..frames).map(move |i| {
+                DNA::blend(left_dna, right_dna, i as f32 / last, method, model.clone())
+            }),
+        )
+    }
+    /// Compose a `DNA` from gene ranges of several donors, a deterministic alternative to the
+    /// crossover of `DNA::merge`. Donors need the same layout, compatible conditions and no
+    /// different models, the encoding and conditions come from the first donor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Mixing, DNA};
+    ///
+    /// let coarse = DNA::new(4, 2);
+    /// let fine = DNA::new(4, 2);
+    /// let mixing: Mixing = "0..2=0, 2..4=1".parse().unwrap();
+    ///
+    /// let mixed = DNA::mix(&[&coarse, &fine], &mixing).unwrap();
+    ///
+    /// assert!(mixed.genes[0] == coarse.genes[0]);
+    /// assert!(mixed.genes[3] == fine.genes[3]);
+    /// ```
+    pub fn mix(donors: &[&DNA], mixing: &Mixing) -> Result<DNA, Error> {
+        let first = donors
+            .first()
+            .ok_or(Error::InvalidSetting("mixing needs at least one donor"))?;
+        if donors.iter().any(|d| !DNA::is_mixable(first, d)) {
+            return Err(Error::InvalidSetting(
+                "donors need the same layout, compatible conditions and model",
+            ));
+        }
+        mixing.validate(first.pool_size, donors.len())?;
+        let mut dna = (*first).clone();
+        dna.model = donors.iter().find_map(|d| d.model.clone());
+        for (range, donor) in mixing.ranges.iter() {
+            for i in range.clone() {
+                dna.genes[i as usize] = donors[*donor].genes[i as usize].clone();
+            }
+        }
+        Ok(dna)
+    }
+    /// Check if two `DNA` have the same layout, compatible conditions and no different models
+    fn is_mixable(left_dna: &DNA, right_dna: &DNA) -> bool {
+        left_dna.get_layout() == right_dna.get_layout()
+            && DNA::merge_model(left_dna, right_dna).is_some()
+            && left_dna.conditions.len() == right_dna.conditions.len()
+            && left_dna
+                .conditions
+                .iter()
+                .zip(right_dna.conditions.iter())
+                .all(|(l, r)| Condition::is_compatible(l, r))
+    }
+    /// Map a `DNA` onto another layout, so it survives a model with a different latent size.
+    ///
+    /// Influences stay with the gene of the same index, new genes get an influence of zero.
+    /// Encoding and conditions are kept, the model is cleared as it takes the old layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Layout, Projection, DNA};
+    ///
+    /// let dna1 = DNA::new(4, 2);
+    /// let layout = Layout {
+    ///     pool_size: 2,
+    ///     gene_size: 4,
+    /// };
+    ///
+    /// let dna2 = dna1.project(layout, &Projection::Rechunk).unwrap();
+    /// let dna3 = dna2.project(dna1.get_layout(), &Projection::Rechunk).unwrap();
+    ///
+    /// assert_eq!(dna2.get_layout(), layout);
+    /// assert_eq!(dna3.get_layout(), dna1.get_layout());
+    /// ```
+    pub fn project(&self, layout: Layout, projection: &Projection) -> Result<DNA, Error> {
+        if layout.gene_size == 0 {
+            return Err(Error::InvalidSetting("gene size needs to be more than 0"));
+        }
+        let markers = self.to_latent_vec_with(LatentMode::Markers);
+        let dim = layout.pool_size as usize * layout.gene_size as usize;
+        let projected = match projection {
+            Projection::Resize => project::resize(&markers, self.get_layout(), layout),
+            Projection::Rechunk => project::rechunk(&markers, dim),
+            Projection::Matrix(matrix) => {
+                if matrix.len() != dim * markers.len() {
+                    return Err(Error::LengthMismatch {
+                        expected: dim * markers.len(),
+                        found: matrix.len(),
+                    });
+                }
+                project::multiply(matrix, &markers, dim)
+            }
+        };
+        let influences = (0..layout.pool_size as usize)
+            .map(|i| self.genes.get(i).map_or(0.0, |g| g.get_influence()))
+            .collect::<Vec<f32>>();
+        let mut dna = DNA::from_latent_vec(
+            &projected,
+            layout.gene_size,
+            Influence::Separate(influences),
+        )?;
+        dna.encoding = self.encoding;
+        dna.conditions = self.conditions.clone();
+        Ok(dna)
+    }
+    /// Compare two `DNA` similarity, return the percentage of same genes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(256, 2);
+    /// let dna_str = dna1.to_string();
+    /// let dna2 = DNA::new(256, 2);
+    ///
+    /// let merged = DNA::merge(dna1, dna2, false).unwrap();
+    ///
+    /// let is_parent = DNA::compare(DNA::from(dna_str), merged) > 0.3;
+    /// ```
+    pub fn compare(left_dna: DNA, right_dna: DNA) -> f64 {
+        let mut same_markers = 0;
+        if left_dna.pool_size != right_dna.pool_size {
+            return 0 as f64;
+        }
+        (0..left_dna.pool_size).for_each(|i| {
+            if left_dna.genes[i as usize].to_string() == right_dna.genes[i as usize].to_string() {
+                same_markers = same_markers + 1;
+            }
+        });
+        same_markers as f64 / left_dna.pool_size as f64
+    }
+    /// Mutate each gene and condition with probability `rate`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let mut dna1 = DNA::new(2, 2);
+    ///
+    /// dna1.mutate(0.1);
+    /// ```
+    pub fn mutate(&mut self, rate: f32) {
+        let mut rng = thread_rng();
+        for gene in self.genes.iter_mut() {
+            if uniform::uniform_f32(&mut rng) < rate {
+                gene.mutate();
+            }
+        }
+        for condition in self.conditions.iter_mut() {
+            if uniform::uniform_f32(&mut rng) < rate {
+                condition.mutate();
+            }
+        }
+    }
+    /// Get the conditioning inputs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new_conditional(2, 2, &[10, 4]);
+    ///
+    /// assert_eq!(dna1.get_conditions().len(), 2);
+    /// ```
+    pub fn get_conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+    /// Set the conditioning inputs, at most as many as fit in a DNA string section
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Condition, DNA};
+    ///
+    /// let mut dna1 = DNA::new(2, 2);
+    ///
+    /// dna1.set_conditions(vec![Condition::from_class(10, 3)]).unwrap();
+    ///
+    /// assert!(dna1.set_conditions(vec![Condition::new(2); 20000]).is_err());
+    /// assert_eq!(dna1.get_conditions(), &[Condition::from_class(10, 3)]);
+    /// ```
+    pub fn set_conditions(&mut self, conditions: Vec<Condition>) -> Result<(), Error> {
+        if conditions.len() > section::MAX_CONDITIONS {
+            return Err(Error::TooLarge {
+                limit: section::MAX_CONDITIONS,
+                found: conditions.len(),
+            });
+        }
+        self.conditions = conditions;
+        Ok(())
+    }
+    /// Get how markers are written in the DNA string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Encoding, DNA};
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// assert_eq!(dna1.get_encoding(), Encoding::F32);
+    /// ```
+    pub fn get_encoding(&self) -> Encoding {
+        self.encoding
+    }
+    /// Set how markers are written, the scale of a quantized encoding needs to be finite and
+    /// more than 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Encoding, DNA};
+    ///
+    /// let mut dna1 = DNA::new(2, 2);
+    ///
+    /// dna1.set_encoding(Encoding::U8(3.0)).unwrap();
+    ///
+    /// assert!(dna1.set_encoding(Encoding::U4(-1.0)).is_err());
+    /// assert_eq!(dna1.get_encoding(), Encoding::U8(3.0));
+    /// ```
+    pub fn set_encoding(&mut self, encoding: Encoding) -> Result<(), Error> {
+        encoding.validate()?;
+        self.encoding = encoding;
+        Ok(())
+    }
+    /// Round every marker to its encoded value, so the latent matches a decoded DNA string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Encoding, DNA};
+    ///
+    /// let mut dna1 = DNA::new(2, 2);
+    /// dna1.set_encoding(Encoding::U8(3.0)).unwrap();
+    ///
+    /// dna1.quantize();
+    ///
+    /// assert_eq!(dna1.to_latent_vec(), DNA::from(dna1.to_string()).to_latent_vec());
+    /// ```
+    pub fn quantize(&mut self) {
+        let encoding = self.encoding;
+        for marker in self.genes.iter_mut().flat_map(|g| g.markers.iter_mut()) {
+            marker.value = encoding.quantize(marker.value);
+        }
+    }
+    /// Pull the markers toward `mean` by `psi`, the truncation trick. `mean` is the markers
+    /// only latent, the zero mean of the prior when `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Influence, LatentMode, DNA};
+    ///
+    /// let mut dna1 = DNA::from_latent_vec(&[1.0, 2.0, -2.0], 2, Influence::Included).unwrap();
+    ///
+    /// dna1.truncate(None, 0.5).unwrap();
+    ///
+    /// assert_eq!(dna1.to_latent_vec_with(LatentMode::Markers), vec![1.0, -1.0]);
+    /// ```
+    pub fn truncate(&mut self, mean: Option<&[f32]>, psi: f32) -> Result<(), Error> {
+        if !psi.is_finite() {
+            return Err(Error::InvalidSetting("psi needs to be a finite number"));
+        }
+        let dim = self.latent_dim(LatentMode::Markers);
+        let zero = vec![0.0; dim];
+        let mean = mean.unwrap_or(&zero);
+        if mean.len() != dim {
+            return Err(Error::LengthMismatch {
+                expected: dim,
+                found: mean.len(),
+            });
+        }
+        for (marker, mean) in self.markers_mut().zip(mean.iter()) {
+            *marker = mean + (*marker - mean) * psi;
+        }
+        Ok(())
+    }
+    /// Clip every marker to `-sigma..=sigma`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Influence, DNA};
+    ///
+    /// let mut dna1 = DNA::from_latent_vec(&[3.0, 2.5, -0.5], 2, Influence::Included).unwrap();
+    ///
+    /// dna1.clip(2.0).unwrap();
+    ///
+    /// assert_eq!(dna1.to_latent_vec(), vec![3.0, 2.0, -0.5]);
+    /// ```
+    pub fn clip(&mut self, sigma: f32) -> Result<(), Error> {
+        if sigma.is_nan() || sigma <= 0.0 {
+            return Err(Error::InvalidSetting("sigma needs to be a positive number"));
+        }
+        for marker in self.markers_mut() {
+            *marker = marker.clamp(-sigma, sigma);
+        }
+        Ok(())
+    }
+    /// Scale the markers onto the hypersphere of radius `sqrt(dim)`, the norm expected of a
+    /// standard normal latent. A zero latent is left as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Influence, DNA};
+    ///
+    /// let latent = [0.5, 2.0, -2.0, 2.0, 2.0];
+    /// let mut dna1 = DNA::from_latent_vec(&latent, 4, Influence::Included).unwrap();
+    ///
+    /// dna1.normalize();
+    ///
+    /// assert_eq!(dna1.to_latent_vec(), vec![0.5, 1.0, -1.0, 1.0, 1.0]);
+    /// ```
+    pub fn normalize(&mut self) {
+        let latent = self.to_latent_vec_with(LatentMode::Markers);
+        let norm = latent.iter().map(|m| m * m).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            return;
+        }
+        let scale = (latent.len() as f32).sqrt() / norm;
+        for marker in self.markers_mut() {
+            *marker *= scale;
+        }
+    }
+    /// Get the mean markers only latent of a population with the same layout
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let population = (0..16).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    ///
+    /// let mean = DNA::mean_latent(&population).unwrap();
+    ///
+    /// assert_eq!(mean.len(), 4);
+    /// ```
+    pub fn mean_latent(population: &[DNA]) -> Option<Vec<f32>> {
+        let layout = population.first()?.get_layout();
+        if population.iter().any(|d| d.get_layout() != layout) {
+            return None;
+        }
+        let mut mean = vec![0.0; population[0].latent_dim(LatentMode::Markers)];
+        for dna in population.iter() {
+            for (sum, marker) in mean
+                .iter_mut()
+                .zip(dna.to_latent_vec_with(LatentMode::Markers))
+            {
+                *sum += marker;
+            }
+        }
+        Some(mean.iter().map(|m| m / population.len() as f32).collect())
+    }
+    /// Convert DNA to GAN latent vector, each gene is its influence followed by its markers.
+    /// Use `to_latent_vec_with` for the other layouts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// let latent = dna1.to_latent_vec();
+    /// ```
+    pub fn to_latent_vec(&self) -> Vec<f32> {
+        self.genes
+            .iter()
+            .map(|g| g.markers.iter().map(|m| m.value).collect::<Vec<f32>>())
+            .collect::<Vec<Vec<f32>>>()
+            .concat()
+    }
+    /// Convert DNA to GAN latent vector laid out by `mode`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{LatentMode, DNA};
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// let latent = dna1.to_latent_vec_with(LatentMode::Markers);
+    ///
+    /// assert_eq!(latent.len(), 4);
+    /// assert_eq!(dna1.to_latent_vec_with(LatentMode::Included), dna1.to_latent_vec());
+    /// ```
+    pub fn to_latent_vec_with(&self, mode: LatentMode) -> Vec<f32> {
+        match mode {
+            LatentMode::Included => self.to_latent_vec(),
+            LatentMode::Markers => self.genes.iter().flat_map(|g| g.get_markers()).collect(),
+            LatentMode::Appended => self
+                .genes
+                .iter()
+                .flat_map(|g| g.get_markers())
+                .chain(self.genes.iter().map(|g| g.get_influence()))
+                .collect(),
+            LatentMode::Scaled => self
+                .genes
+                .iter()
+                .flat_map(|g| {
+                    let influence = g.get_influence();
+                    g.get_markers().into_iter().map(move |m| m * influence)
+                })
+                .collect(),
+        }
+    }
+    /// Convert DNA to GAN latent vector for `model`, refused when the DNA is made for another
+    /// model or the model takes another layout
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{LatentMode, Model, DNA};
+    ///
+    /// let mut dna1 = DNA::new(2, 2);
+    /// let model = Model::new("faces-v2", dna1.get_layout()).unwrap();
+    /// dna1.model = Some(model.clone());
+    ///
+    /// let latent = dna1.to_latent_vec_for(&model, LatentMode::Markers).unwrap();
+    ///
+    /// let other = Model::new("faces-v3", dna1.get_layout()).unwrap();
+    /// assert!(dna1.to_latent_vec_for(&other, LatentMode::Markers).is_err());
+    /// ```
+    pub fn to_latent_vec_for(&self, model: &Model, mode: LatentMode) -> Result<Vec<f32>, Error> {
+        self.check_model(model)?;
+        Ok(self.to_latent_vec_with(mode))
+    }
+    /// Check the DNA can be fed to `model`. DNA without a model only needs the same layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Model, DNA};
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    /// let model = Model::new("faces-v2", dna1.get_layout()).unwrap();
+    ///
+    /// assert!(dna1.check_model(&model).is_ok());
+    /// assert!(DNA::new(2, 3).check_model(&model).is_err());
+    /// ```
+    pub fn check_model(&self, model: &Model) -> Result<(), Error> {
+        let same_model = self.model.as_ref().map_or(true, |m| m == model);
+        match same_model && model.takes(self.get_layout()) {
+            true => Ok(()),
+            false => Err(Error::ModelMismatch),
+        }
+    }
+    /// Get the length of the latent vector written by `to_latent_vec_with`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{LatentMode, DNA};
+    ///
+    /// let dna1 = DNA::new(4, 8);
+    ///
+    /// assert_eq!(dna1.latent_dim(LatentMode::Included), 36);
+    /// assert_eq!(dna1.latent_dim(LatentMode::Scaled), 32);
+    /// ```
+    pub fn latent_dim(&self, mode: LatentMode) -> usize {
+        let pool_size = self.pool_size as usize;
+        let gene_size = self.gene_size as usize;
+        match mode {
+            LatentMode::Included | LatentMode::Appended => pool_size * (gene_size + 1),
+            LatentMode::Markers | LatentMode::Scaled => pool_size * gene_size,
+        }
+    }
+    /// Constructs a `DNA` from a GAN latent vector split into genes of `gene_size` markers
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Influence, DNA};
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    /// let latent = dna1.to_latent_vec();
+    ///
+    /// let dna_copy = DNA::from_latent_vec(&latent, 2, Influence::Included).unwrap();
+    /// let dna2 = DNA::from_latent_vec(&[0.1, 0.2, 0.3, 0.4], 2, Influence::Default(1.0)).unwrap();
+    ///
+    /// assert_eq!(dna_copy.to_string(), dna1.to_string());
+    /// assert_eq!(dna2.to_latent_vec(), vec![1.0, 0.1, 0.2, 1.0, 0.3, 0.4]);
+    /// assert!(DNA::from_latent_vec(&[0.1, 0.2, 0.3], 2, Influence::Default(1.0)).is_err());
+    /// ```
+    pub fn from_latent_vec(
+        latent: &[f32],
+        gene_size: u16,
+        influence: Influence,
+    ) -> Result<DNA, Error> {
+        if gene_size == 0 {
+            return Err(Error::InvalidSetting("gene size needs to be more than 0"));
+        }
+        let width = match influence {
+            Influence::Included => gene_size as usize + 1,
+            _ => gene_size as usize,
+        };
+        if latent.len() % width != 0 {
+            return Err(Error::LengthMismatch {
+                expected: latent.len().next_multiple_of(width),
+                found: latent.len(),
+            });
+        }
+        let pool_size = latent.len() / width;
+        if pool_size > u16::MAX as usize {
+            return Err(Error::InvalidSetting("pool size needs to fit in 16 bits"));
+        }
+        let influences = match influence {
+            Influence::Included => Vec::new(),
+            Influence::Separate(influences) => influences,
+            Influence::Default(value) => vec![value; pool_size],
+        };
+        if width == gene_size as usize && influences.len() != pool_size {
+            return Err(Error::LengthMismatch {
+                expected: pool_size,
+                found: influences.len(),
+            });
+        }
+        let genes = latent
+            .chunks(width)
+            .enumerate()
+            .map(|(i, markers)| match influences.get(i) {
+                Some(influence) => Gene::from([&[*influence], markers].concat()),
+                None => Gene::from(markers.to_vec()),
+            })
+            .collect();
+        Ok(DNA::from_genes(gene_size, genes))
+    }
+    /// Convert the conditions to concatenated one-hot vectors, not part of the latent vector
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new_conditional(2, 2, &[10]);
+    ///
+    /// let condition = dna1.to_condition_vec();
+    ///
+    /// assert_eq!(condition.len(), 10);
+    /// ```
+    pub fn to_condition_vec(&self) -> Vec<f32> {
+        self.conditions
+            .iter()
+            .map(|c| c.to_one_hot())
+            .collect::<Vec<Vec<f32>>>()
+            .concat()
+    }
+    /// Convert the conditions to embedding indices, one per condition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new_conditional(2, 2, &[10, 4]);
+    ///
+    /// let indices = dna1.to_condition_indices();
+    ///
+    /// assert_eq!(indices.len(), 2);
+    /// ```
+    pub fn to_condition_indices(&self) -> Vec<usize> {
+        self.conditions
+            .iter()
+            .map(|c| c.embedding_index())
+            .collect()
+    }
+    /// Convert DNA to string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// let dna1_str = dna1.to_string();
+    /// ```
+    pub fn to_string(&self) -> String {
+        let pool_size_hex: String = utils::u16_to_string(self.pool_size);
+        let gene_size_hex: String = utils::u16_to_string(self.gene_size);
+        let check_sum: String = utils::f32_to_string(self.get_check_sum());
+        let sections = self.sections();
+        // Extended header is marked by a zero gene size, legacy DNA never has one
+        let header_hex: String = if sections.is_empty() {
+            gene_size_hex
+        } else {
+            format!(
+                "{}{}{}{}",
+                utils::u16_to_string(0),
+                gene_size_hex,
+                utils::u8_to_string(sections.len() as u8),
+                section::to_string(&sections)
+            )
+        };
+
+        format!(
+            "{}{}{}{}",
+            check_sum,
+            pool_size_hex,
+            header_hex,
+            self.genes
+                .iter()
+                .flat_map(|g| g.markers.iter())
+                .map(|m| self.encoding.marker_to_string(m.value))
+                .collect::<String>()
+        )
+    }
+    /// Convert DNA to Bech32m with the human readable prefix `hrp`, like `dna1...`. The prefix
+    /// needs to be non-empty printable ASCII without spaces.
     ///
     /// # Examples
     ///
     /// ```
     /// use genome::DNA;
     ///
-    /// let dna = DNA::new(2, 2);
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// let dna1_str = dna1.to_bech32("dna").unwrap();
+    ///
+    /// assert!(dna1_str.starts_with("dna1"));
+    /// assert!(dna1.to_bech32("my dna").is_err());
     /// ```
-    pub fn new(pool_size: u16, gene_size: u16) -> DNA {
-        DNA {
-            pool_size: pool_size,
-            gene_size: gene_size,
-            genes: (0..pool_size).map(|_| Gene::new(gene_size)).collect(),
-        }
+    pub fn to_bech32(&self, hrp: &str) -> Result<String, Error> {
+        bech32::encode(hrp, &self.to_bytes())
     }
-    /// Check if current DNA string is valid.
+    /// Convert Bech32m to DNA, the prefix needs to be `hrp`. A failed checksum reports
+    /// the positions of likely typos.
     ///
     /// # Examples
     ///
     /// ```
     /// use genome::DNA;
-    /// let dna = DNA::new(2, 2);
     ///
-    /// let is_valid = DNA::is_valid(dna.to_string());
+    /// let dna1 = DNA::new(2, 2);
+    /// let dna1_str = dna1.to_bech32("dna").unwrap();
+    ///
+    /// let dna_copy = DNA::from_bech32(&dna1_str, "dna").unwrap();
     /// ```
-    pub fn is_valid(dna_str: String) -> bool {
-        let pool_size_hex = &dna_str[0..8];
-        let dna = DNA::from(dna_str.clone());
-        if dna.get_sum() == utils::f32_from_str(pool_size_hex) {
-            return true;
+    pub fn from_bech32(dna_str: &str, hrp: &str) -> Result<DNA, Error> {
+        let (prefix, data) = bech32::decode(dna_str)?;
+        if prefix != hrp.to_lowercase() {
+            return Err(Error::Malformed("unexpected prefix"));
         }
-        return false;
+        DNA::from_bytes(&data)
     }
-    /// Merge two `DNA` into one
+    /// Convert DNA to Base58
     ///
     /// # Examples
     ///
@@ -54,67 +1119,47 @@ impl DNA {
     /// use genome::DNA;
     ///
     /// let dna1 = DNA::new(2, 2);
-    /// let dna2 = DNA::new(2, 2);
     ///
-    /// let merged = DNA::merge(dna1, dna2, false);
+    /// let dna1_str = dna1.to_base58();
     /// ```
-    pub fn merge(left_dna: DNA, right_dna: DNA, mutate: bool) -> Option<DNA> {
-        let mut ration_rng = thread_rng();
-        let mut mutate_rng = thread_rng();
-        match (left_dna.pool_size == right_dna.pool_size)
-            && (left_dna.gene_size == right_dna.gene_size)
-        {
-            true => Some(DNA {
-                pool_size: left_dna.pool_size,
-                gene_size: left_dna.gene_size,
-                genes: (0..left_dna.pool_size)
-                    .map(|i| {
-                        if ration_rng.gen::<f32>() >= 0.5 {
-                            left_dna.genes[i as usize].to_string()
-                        } else {
-                            right_dna.genes[i as usize].to_string()
-                        }
-                    })
-                    .map(|g| {
-                        let mut gene = Gene::from(g);
-                        if mutate && mutate_rng.gen::<f32>() >= 0.9 {
-                            gene.mutate();
-                        }
-                        gene
-                    })
-                    .collect(),
-            }),
-            false => None,
-        }
+    pub fn to_base58(&self) -> String {
+        base58::encode(&self.to_bytes())
     }
-    /// Compare two `DNA` similarity, return the percentage of same genes
+    /// Convert Base58 to DNA
     ///
     /// # Examples
     ///
     /// ```
     /// use genome::DNA;
     ///
-    /// let dna1 = DNA::new(256, 2);
-    /// let dna_str = dna1.to_string();
-    /// let dna2 = DNA::new(256, 2);
+    /// let dna1 = DNA::new(2, 2);
+    /// let dna1_str = dna1.to_base58();
     ///
-    /// let merged = DNA::merge(dna1, dna2, false).unwrap();
+    /// let dna_copy = DNA::from_base58(&dna1_str).unwrap();
+    /// ```
+    pub fn from_base58(dna_str: &str) -> Result<DNA, Error> {
+        DNA::from_bytes(&base58::decode(dna_str)?)
+    }
+    /// Get the layout of the dna
+    ///
+    /// # Examples
     ///
-    /// let is_parent = DNA::compare(DNA::from(dna_str), merged) > 0.3;
     /// ```
-    pub fn compare(left_dna: DNA, right_dna: DNA) -> f64 {
-        let mut same_markers = 0;
-        if left_dna.pool_size != right_dna.pool_size {
-            return 0 as f64;
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(2, 3);
+    ///
+    /// let layout = dna1.get_layout();
+    ///
+    /// assert_eq!(layout.gene_size, 3);
+    /// ```
+    pub fn get_layout(&self) -> Layout {
+        Layout {
+            pool_size: self.pool_size,
+            gene_size: self.gene_size,
         }
-        (0..left_dna.pool_size).for_each(|i| {
-            if left_dna.genes[i as usize].to_string() == right_dna.genes[i as usize].to_string() {
-                same_markers = same_markers + 1;
-            }
-        });
-        same_markers as f64 / left_dna.pool_size as f64
     }
-    /// Convert DNA to GAN latent vector
+    /// Convert DNA to compact bytes, lossless against the string form
     ///
     /// # Examples
     ///
@@ -123,16 +1168,14 @@ impl DNA {
     ///
     /// let dna1 = DNA::new(2, 2);
     ///
-    /// let latent = dna1.to_latent_vec();
+    /// let dna1_bytes = dna1.to_bytes();
+    ///
+    /// assert_eq!(dna1_bytes.len(), 3 + 2 * 3 * 4 + 4);
     /// ```
-    pub fn to_latent_vec(&self) -> Vec<f32> {
-        self.genes
-            .iter()
-            .map(|g| g.markers.iter().map(|m| m.value).collect::<Vec<f32>>())
-            .collect::<Vec<Vec<f32>>>()
-            .concat()
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary::to_bytes(self)
     }
-    /// Convert DNA to string
+    /// Convert compact bytes to DNA, checking the integrity trailer
     ///
     /// # Examples
     ///
@@ -141,20 +1184,12 @@ impl DNA {
     ///
     /// let dna1 = DNA::new(2, 2);
     ///
-    /// let dna1_str = dna1.to_string();
+    /// let dna_copy = DNA::from_bytes(&dna1.to_bytes()).unwrap();
+    ///
+    /// assert_eq!(dna_copy.to_string(), dna1.to_string());
     /// ```
-    pub fn to_string(&self) -> String {
-        let pool_size_hex: String = utils::u16_to_string(self.pool_size);
-        let gene_size_hex: String = utils::u16_to_string(self.gene_size);
-        let check_sum: String = utils::f32_to_string(self.get_sum());
-
-        format!(
-            "{}{}{}{}",
-            check_sum,
-            pool_size_hex,
-            gene_size_hex,
-            self.genes.iter().map(|m| m.to_string()).collect::<String>()
-        )
+    pub fn from_bytes(data: &[u8]) -> Result<DNA, Error> {
+        binary::from_bytes(data)
     }
     /// Get checksum for the dna, return back f32 sum of all genes.
     ///
@@ -170,6 +1205,76 @@ impl DNA {
     pub fn get_sum(&self) -> f32 {
         self.genes.iter().map(|g| g.get_sum()).sum()
     }
+    /// Get the SHA-256 hash of the binary DNA, used to refer to a DNA as a parent
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// assert_eq!(dna1.get_hash(), dna1.clone().get_hash());
+    /// ```
+    pub fn get_hash(&self) -> [u8; 32] {
+        Sha256::digest(self.to_bytes()).into()
+    }
+    /// Get checksum stored in the DNA string, the sum of all encoded genes and condition classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::DNA;
+    ///
+    /// let dna1 = DNA::new(2, 2);
+    ///
+    /// assert_eq!(dna1.get_check_sum(), dna1.get_sum());
+    /// ```
+    pub fn get_check_sum(&self) -> f32 {
+        let encoding = self.encoding;
+        self.genes
+            .iter()
+            .map(|g| {
+                g.markers
+                    .iter()
+                    .map(|m| encoding.quantize(m.value))
+                    .sum::<f32>()
+            })
+            .sum::<f32>()
+            + self
+                .conditions
+                .iter()
+                .map(|c| c.class_id as f32)
+                .sum::<f32>()
+    }
+    /// Iterate the markers of every gene, without influence
+    fn markers_mut(&mut self) -> impl Iterator<Item = &mut f32> {
+        self.genes
+            .iter_mut()
+            .flat_map(|g| g.markers.iter_mut().skip(1).map(|m| &mut m.value))
+    }
+    fn apply_sections(&mut self, sections: Vec<Section>) {
+        for section in sections {
+            match section {
+                Section::Conditions(conditions) => self.conditions = conditions,
+                Section::Encoding(encoding) => self.encoding = encoding,
+                Section::Model(model) => self.model = Some(model),
+            }
+        }
+    }
+    fn sections(&self) -> Vec<Section> {
+        let mut sections = Vec::new();
+        if self.encoding != Encoding::F32 {
+            sections.push(Section::Encoding(self.encoding));
+        }
+        if !self.conditions.is_empty() {
+            sections.push(Section::Conditions(self.conditions.clone()));
+        }
+        if let Some(model) = &self.model {
+            sections.push(Section::Model(model.clone()));
+        }
+        sections
+    }
 }
 
 /// Convert DNA to string
@@ -185,17 +1290,7 @@ impl DNA {
 /// ```
 impl std::convert::From<DNA> for String {
     fn from(dna: DNA) -> String {
-        let pool_size_hex: String = utils::u16_to_string(dna.pool_size);
-        let gene_size_hex: String = utils::u16_to_string(dna.gene_size);
-        let check_sum: String = utils::f32_to_string(dna.get_sum());
-
-        format!(
-            "{}{}{}{}",
-            check_sum,
-            pool_size_hex,
-            gene_size_hex,
-            dna.genes.iter().map(|m| m.to_string()).collect::<String>()
-        )
+        dna.to_string()
     }
 }
 
@@ -213,23 +1308,79 @@ impl std::convert::From<DNA> for String {
 /// ```
 impl std::convert::From<String> for DNA {
     fn from(dna: String) -> DNA {
-        // Ignore the first 8 char for checksum
-        let pool_size_hex = &dna[8..12];
-        let gene_size_hex = &dna[12..16];
-        let genes_hex = &dna[16..];
+        dna.parse().expect("Invalid DNA string")
+    }
+}
 
-        let gene_size = utils::u16_from_str(gene_size_hex);
+/// Parse string to DNA, the checksum is not checked
+///
+/// # Examples
+///
+/// ```
+/// use genome::DNA;
+///
+/// let dna1 = DNA::new(2, 2);
+/// let dna1_str = dna1.to_string();
+///
+/// let dna_copy: DNA = dna1_str.parse().unwrap();
+///
+/// assert!("not a dna".parse::<DNA>().is_err());
+/// ```
+impl std::str::FromStr for DNA {
+    type Err = Error;
 
-        let genes = utils::partition_str(genes_hex, 8 * (gene_size + 1) as usize)
-            .iter()
-            .map(|s| String::from(*s))
-            .collect::<Vec<String>>();
+    fn from_str(dna: &str) -> Result<DNA, Error> {
+        if !dna.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::Malformed("DNA string needs to be hex"));
+        }
+        if dna.len() < 16 {
+            return Err(Error::Malformed("DNA string is missing its header"));
+        }
+        // Ignore the first 8 char for checksum
+        let pool_size = utils::u16_from_str(&dna[8..12]);
+        let mut gene_size = utils::u16_from_str(&dna[12..16]);
+        let mut sections = Vec::new();
+        let mut cursor = 16;
+        if gene_size == 0 {
+            if dna.len() < 22 {
+                return Err(Error::Malformed("DNA string is missing its header"));
+            }
+            gene_size = utils::u16_from_str(&dna[16..20]);
+            let count = utils::u8_from_str(&dna[20..22]);
+            let (parsed, length) = section::from_str(&dna[22..], count)?;
+            sections = parsed;
+            cursor = 22 + length;
+        }
+        if gene_size == 0 {
+            return Err(Error::Malformed("gene size needs to be more than 0"));
+        }
+        let genes_hex = &dna[cursor..];
 
-        DNA {
-            pool_size: utils::u16_from_str(pool_size_hex),
-            gene_size,
-            genes: genes.iter().map(|g| Gene::from(g.clone())).collect(),
+        let encoding = section::get_encoding(&sections);
+        let marker_width = encoding.marker_width();
+        let expected = pool_size as usize * (gene_size as usize + 1) * marker_width;
+        if genes_hex.len() != expected {
+            return Err(Error::LengthMismatch {
+                expected,
+                found: genes_hex.len(),
+            });
         }
+        let genes = utils::partition_str(genes_hex, marker_width * (gene_size as usize + 1))
+            .iter()
+            .map(|g| {
+                Gene::from(
+                    utils::partition_str(g, marker_width)
+                        .iter()
+                        .map(|m| encoding.marker_from_str(m))
+                        .collect::<Vec<f32>>(),
+                )
+            })
+            .collect();
+
+        let mut dna = DNA::from_genes(gene_size, genes);
+        dna.encoding = encoding;
+        dna.apply_sections(sections);
+        Ok(dna)
     }
 }
 
@@ -277,4 +1428,516 @@ mod tests {
         assert!(parent2_ratio != 0 as f64);
         assert!(parent1_ratio + parent2_ratio == 1 as f64);
     }
+    #[test]
+    fn mutate_with_zero_rate() {
+        let mut dna = DNA::new_conditional(8, 2, &[4]);
+        let dna_str = dna.to_string();
+        dna.mutate(0.0);
+        assert_eq!(dna.to_string(), dna_str);
+    }
+    #[test]
+    fn encodings_can_be_converted_and_back() {
+        for encoding in [
+            Encoding::F16,
+            Encoding::BF16,
+            Encoding::U8(3.0),
+            Encoding::U4(3.0),
+        ]
+        .iter()
+        {
+            let mut dna = DNA::new_conditional(4, 3, &[5]);
+            dna.set_encoding(*encoding).unwrap();
+            let dna_str = dna.to_string();
+            let dna2 = DNA::from(dna_str.clone());
+            assert_eq!(dna2.get_encoding(), *encoding);
+            assert_eq!(dna2.to_string(), dna_str);
+            assert!(DNA::is_valid(dna_str));
+            for (value, decoded) in dna.to_latent_vec().iter().zip(dna2.to_latent_vec()) {
+                assert_eq!(encoding.quantize(*value), decoded);
+            }
+        }
+    }
+    #[test]
+    fn invalid_scale_is_rejected() {
+        let mut dna = DNA::new(2, 2);
+        dna.set_encoding(Encoding::U8(3.0)).unwrap();
+        let dna_str = dna.to_string();
+        // Encoding section holding U8 with a scale of 3.0, then of 0.0
+        let zero_scale = dna_str.replacen("0200050340400000", "0200050300000000", 1);
+        assert_ne!(zero_scale, dna_str);
+        assert_eq!(
+            zero_scale.parse::<DNA>().err(),
+            Some(Error::InvalidSetting("scale needs to be a positive number"))
+        );
+        assert!(dna.set_encoding(Encoding::U4(f32::INFINITY)).is_err());
+        assert!(dna.set_encoding(Encoding::U4(f32::NAN)).is_err());
+        assert!(dna.set_encoding(Encoding::U8(0.0)).is_err());
+        assert_eq!(dna.get_encoding(), Encoding::U8(3.0));
+        assert_eq!(dna.to_string(), dna_str);
+    }
+    #[test]
+    fn half_precision_halves_markers() {
+        let mut dna = DNA::new_conditional(4, 3, &[5]);
+        let f32_len = dna.to_string().len();
+        dna.set_encoding(Encoding::F16).unwrap();
+        // 16 markers at 4 chars less each, plus the 8 char encoding section
+        assert_eq!(f32_len - dna.to_string().len(), 16 * 4 - 8);
+    }
+    #[test]
+    fn bytes_are_lossless() {
+        for encoding in [
+            Encoding::F32,
+            Encoding::F16,
+            Encoding::BF16,
+            Encoding::U8(3.0),
+            Encoding::U4(3.0),
+        ]
+        .iter()
+        {
+            let mut dna = DNA::new_conditional(3, 4, &[7, 300]);
+            dna.set_encoding(*encoding).unwrap();
+            let dna_str = dna.to_string();
+            let dna_bytes = dna.to_bytes();
+            assert_eq!(DNA::from_bytes(&dna_bytes).unwrap().to_string(), dna_str);
+            assert_eq!(DNA::from(dna_str).to_bytes(), dna_bytes);
+        }
+    }
+    #[test]
+    fn bytes_are_half_of_hex() {
+        let dna = DNA::new(200, 8);
+        // varint sizes and trailer against 16 chars of hex header
+        assert_eq!(
+            dna.to_string().len() - 16,
+            (dna.to_bytes().len() - 2 - 1 - 1 - 4) * 2
+        );
+    }
+    #[test]
+    fn corrupt_bytes_are_rejected() {
+        let dna = DNA::new(4, 4);
+        let mut dna_bytes = dna.to_bytes();
+        dna_bytes[5] ^= 0x01;
+        assert_eq!(
+            DNA::from_bytes(&dna_bytes).err(),
+            Some(Error::ChecksumMismatch)
+        );
+        assert!(DNA::from_bytes(&dna_bytes[..3]).is_err());
+        let mut body = dna.to_bytes();
+        body.truncate(body.len() - 5);
+        let check_sum = utils::crc32(&body);
+        body.extend_from_slice(&check_sum.to_le_bytes());
+        assert_eq!(
+            DNA::from_bytes(&body).err(),
+            Some(Error::LengthMismatch {
+                expected: 4 * 5 * 4,
+                found: 4 * 5 * 4 - 1
+            })
+        );
+    }
+    #[test]
+    fn invalid_strings_are_rejected() {
+        let dna_str = DNA::new(2, 2).to_string();
+        assert!(!DNA::is_valid(String::from("00")));
+        assert!(!DNA::is_valid(dna_str.replace(&dna_str[16..17], "x")));
+        assert!(!DNA::is_valid(String::from(&dna_str[..dna_str.len() - 1])));
+        assert_eq!(
+            dna_str[..dna_str.len() - 8].parse::<DNA>().err(),
+            Some(Error::LengthMismatch {
+                expected: 2 * 3 * 8,
+                found: 2 * 3 * 8 - 8
+            })
+        );
+    }
+    #[test]
+    fn text_encodings_round_trip() {
+        let mut dna = DNA::new_conditional(8, 3, &[4]);
+        dna.set_encoding(Encoding::F16).unwrap();
+        let dna_str = dna.to_string();
+        let bech32_str = dna.to_bech32("dna").unwrap();
+        assert_eq!(
+            DNA::from_bech32(&bech32_str, "dna").unwrap().to_string(),
+            dna_str
+        );
+        assert!(DNA::from_bech32(&bech32_str, "cat").is_err());
+        let base58_str = dna.to_base58();
+        assert_eq!(DNA::from_base58(&base58_str).unwrap().to_string(), dna_str);
+    }
+    #[test]
+    fn bech32_reports_typo() {
+        let bech32_str = DNA::new(2, 2).to_bech32("dna").unwrap();
+        let mut typo = bech32_str.clone().into_bytes();
+        typo[20] = if typo[20] == b'x' { b'y' } else { b'x' };
+        match DNA::from_bech32(&String::from_utf8(typo).unwrap(), "dna") {
+            Err(Error::Typo { positions }) => assert!(positions.contains(&20)),
+            _ => panic!("typo not located"),
+        }
+    }
+    #[test]
+    fn derive_is_stable() {
+        // Golden vectors, changing these breaks every derived DNA
+        let layout = Layout {
+            pool_size: 2,
+            gene_size: 3,
+        };
+        assert_eq!(
+            DNA::derive(b"genome", layout).to_string(),
+            "3ff5f74a000200033e519be2bf12ace7bd63c0333fb649dd3fbb98debe62718c3eea744dbf47f2b1"
+        );
+        let layout = Layout {
+            pool_size: 1,
+            gene_size: 1,
+        };
+        assert_eq!(
+            DNA::derive(b"", layout).to_string(),
+            "3e82ba4d00010001befb603d3f3f0d45"
+        );
+        assert_ne!(
+            DNA::derive(b"genome1", layout).to_string(),
+            DNA::derive(b"genome", layout).to_string()
+        );
+    }
+    #[test]
+    #[should_panic]
+    fn derive_needs_markers() {
+        // A zero gene size is the extended header marker, it cannot round-trip
+        let layout = Layout {
+            pool_size: 0,
+            gene_size: 0,
+        };
+        DNA::derive(b"genome", layout);
+    }
+    #[test]
+    fn seeded_generation_is_stable() {
+        // Golden vectors, changing these breaks every seeded DNA
+        let layout = Layout {
+            pool_size: 2,
+            gene_size: 3,
+        };
+        assert_eq!(
+            DNA::from_seed(42, layout).to_string(),
+            "c048b94400020003bf798d9d3f88df23bf89619ebf1510b9bfd38002bec96fc4be83c29c3f3a5775"
+        );
+        let layout = Layout {
+            pool_size: 1,
+            gene_size: 1,
+        };
+        assert_eq!(
+            DNA::from_seed(0, layout).to_string(),
+            "403b147d00010001402d54213e5c05bc"
+        );
+    }
+    #[test]
+    fn seeded_merge_is_stable() {
+        let layout = Layout {
+            pool_size: 6,
+            gene_size: 1,
+        };
+        let child = DNA::merge_with_rng(
+            DNA::derive(b"left", layout),
+            DNA::derive(b"right", layout),
+            true,
+            &mut ChaCha20Rng::seed_from_u64(3),
+        )
+        .unwrap();
+        assert_eq!(
+            child.to_string(),
+            "bfb3eb2a000600013eda1439bf817bd9be8ed30f3ec47e18bf2cde6fbf8457393e279fd5bd4a43653e24227f3f9fea50beba55febec04ecb"
+        );
+    }
+    #[test]
+    fn latent_vec_can_be_converted_and_back() {
+        let dna = DNA::new(8, 4);
+        let latent = dna.to_latent_vec();
+        let dna_copy = DNA::from_latent_vec(&latent, 4, Influence::Included).unwrap();
+        assert_eq!(dna_copy.to_string(), dna.to_string());
+        let markers = dna
+            .genes
+            .iter()
+            .flat_map(|g| g.get_markers())
+            .collect::<Vec<f32>>();
+        let influences = dna.genes.iter().map(|g| g.get_influence()).collect();
+        let dna_copy = DNA::from_latent_vec(&markers, 4, Influence::Separate(influences)).unwrap();
+        assert_eq!(dna_copy.to_string(), dna.to_string());
+    }
+    #[test]
+    fn uneven_latent_is_rejected() {
+        assert_eq!(
+            DNA::from_latent_vec(&[0.0; 7], 2, Influence::Included).err(),
+            Some(Error::LengthMismatch {
+                expected: 9,
+                found: 7
+            })
+        );
+        assert_eq!(
+            DNA::from_latent_vec(&[0.0; 6], 2, Influence::Separate(vec![1.0; 2])).err(),
+            Some(Error::LengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(DNA::from_latent_vec(&[0.0; 6], 0, Influence::Included).is_err());
+    }
+    #[test]
+    fn latent_modes_match_dim() {
+        let dna = DNA::new(3, 2);
+        for mode in [
+            LatentMode::Included,
+            LatentMode::Markers,
+            LatentMode::Appended,
+            LatentMode::Scaled,
+        ]
+        .iter()
+        {
+            assert_eq!(dna.to_latent_vec_with(*mode).len(), dna.latent_dim(*mode));
+        }
+        let dna =
+            DNA::from_latent_vec(&[2.0, 1.0, -1.0, 0.5, 4.0, 2.0], 2, Influence::Included).unwrap();
+        assert_eq!(
+            dna.to_latent_vec_with(LatentMode::Appended),
+            vec![1.0, -1.0, 4.0, 2.0, 2.0, 0.5]
+        );
+        assert_eq!(
+            dna.to_latent_vec_with(LatentMode::Scaled),
+            vec![2.0, -2.0, 2.0, 1.0]
+        );
+    }
+    #[test]
+    fn morph_is_evenly_spaced() {
+        let left = DNA::from_latent_vec(&[1.0, 0.0, 4.0], 2, Influence::Included).unwrap();
+        let right = DNA::from_latent_vec(&[3.0, 2.0, 0.0], 2, Influence::Included).unwrap();
+        let frames = DNA::morph(&left, &right, 3, Interpolation::Linear)
+            .unwrap()
+            .map(|d| d.to_latent_vec())
+            .collect::<Vec<Vec<f32>>>();
+        assert_eq!(
+            frames,
+            vec![
+                vec![1.0, 0.0, 4.0],
+                vec![2.0, 1.0, 2.0],
+                vec![3.0, 2.0, 0.0]
+            ]
+        );
+        let frames = DNA::morph(&left, &right, 4, Interpolation::Spherical)
+            .unwrap()
+            .collect::<Vec<DNA>>();
+        assert_eq!(frames[0].to_string(), left.to_string());
+        assert!(DNA::is_valid(frames[2].to_string()));
+    }
+    #[test]
+    fn incompatible_dna_cannot_be_interpolated() {
+        let left = DNA::new(2, 2);
+        assert!(DNA::interpolate(&left, &DNA::new(2, 3), 0.5, Interpolation::Linear).is_none());
+        let left = DNA::new_conditional(2, 2, &[3]);
+        let right = DNA::new_conditional(2, 2, &[4]);
+        assert!(DNA::morph(&left, &right, 2, Interpolation::Linear).is_none());
+    }
+    #[test]
+    fn legacy_string_is_unchanged() {
+        let dna = DNA::new(2, 2);
+        assert_eq!(dna.to_string().len(), 16 + 2 * 3 * 8);
+        assert!(DNA::is_valid(dna.to_string()));
+    }
+    #[test]
+    fn conditions_can_be_converted_and_back() {
+        let dna = DNA::new_conditional(4, 2, &[10, 3]);
+        let dna2 = DNA::from(dna.to_string());
+        assert_eq!(dna.conditions, dna2.conditions);
+        assert_eq!(dna.to_latent_vec(), dna2.to_latent_vec());
+        assert_eq!(dna.to_string(), dna2.to_string());
+        assert!(DNA::is_valid(dna.to_string()));
+    }
+    #[test]
+    fn conditions_fit_in_a_section() {
+        let mut dna = DNA::new(2, 2);
+        let conditions = vec![Condition::from_class(2, 1); section::MAX_CONDITIONS];
+        dna.set_conditions(conditions.clone()).unwrap();
+        assert_eq!(DNA::from(dna.to_string()).conditions, conditions);
+        let too_many = vec![Condition::from_class(2, 1); section::MAX_CONDITIONS + 1];
+        assert_eq!(
+            dna.set_conditions(too_many),
+            Err(Error::TooLarge {
+                limit: section::MAX_CONDITIONS,
+                found: section::MAX_CONDITIONS + 1
+            })
+        );
+    }
+    #[test]
+    fn unknown_sections_are_skipped() {
+        let dna = DNA::new_conditional(3, 4, &[7]);
+        // An extra section tagged 0x7f with a 2 byte payload, in front of the conditions
+        let dna_str = dna.to_string();
+        let count = utils::u8_from_str(&dna_str[20..22]) + 1;
+        let extended = format!(
+            "{}{}7f0002abcd{}",
+            &dna_str[..20],
+            utils::u8_to_string(count),
+            &dna_str[22..]
+        );
+        assert_eq!(extended.parse::<DNA>().unwrap().to_string(), dna_str);
+        let mut body = binary::write(&dna);
+        body[2] += 1;
+        body.splice(3..3, vec![0x7f, 2, 0xab, 0xcd]);
+        let check_sum = utils::crc32(&body);
+        body.extend_from_slice(&check_sum.to_le_bytes());
+        assert_eq!(DNA::from_bytes(&body).unwrap().to_string(), dna_str);
+        // Known sections are still checked
+        let invalid = format!(
+            "{}{}010003aabbcc{}",
+            &dna_str[..20],
+            utils::u8_to_string(count),
+            &dna_str[22..]
+        );
+        assert_eq!(
+            invalid.parse::<DNA>().err(),
+            Some(Error::Malformed("invalid section"))
+        );
+    }
+    #[test]
+    fn conditions_are_not_in_latent() {
+        let dna = DNA::new_conditional(4, 2, &[10]);
+        assert_eq!(dna.to_latent_vec().len(), 4 * 3);
+        assert_eq!(dna.to_condition_vec().len(), 10);
+        assert_eq!(
+            dna.to_condition_indices(),
+            vec![dna.conditions[0].class_id as usize]
+        );
+    }
+    #[test]
+    fn conditions_are_inherited() {
+        let dna1 = DNA::new_conditional(2, 2, &[2, 5]);
+        let dna2 = DNA::new_conditional(2, 2, &[2, 5]);
+        let parents = (dna1.conditions.clone(), dna2.conditions.clone());
+        let child = DNA::merge(dna1, dna2, false).unwrap();
+        for (i, condition) in child.conditions.iter().enumerate() {
+            assert!(*condition == parents.0[i] || *condition == parents.1[i]);
+        }
+    }
+    #[test]
+    fn conditions_cannot_be_merged() {
+        let dna1 = DNA::new_conditional(2, 2, &[2]);
+        let dna2 = DNA::new_conditional(2, 2, &[3]);
+        assert!(DNA::merge(dna1, dna2, false).is_none());
+        let dna1 = DNA::new_conditional(2, 2, &[2]);
+        let dna2 = DNA::new(2, 2);
+        assert!(DNA::merge(dna1, dna2, false).is_none());
+    }
+    #[test]
+    fn truncation_keeps_influence() {
+        let mut dna1 = DNA::new(4, 4);
+        let latent = dna1.to_latent_vec();
+        let mean = vec![0.5; 16];
+        dna1.truncate(Some(&mean), 0.0).unwrap();
+        for (gene, markers) in dna1.to_latent_vec().chunks(5).zip(latent.chunks(5)) {
+            assert_eq!(gene[0], markers[0]);
+            assert!(gene[1..].iter().all(|m| *m == 0.5));
+        }
+        assert!(dna1.truncate(Some(&mean[1..]), 0.5).is_err());
+        assert!(dna1.truncate(None, f32::NAN).is_err());
+        assert!(dna1.clip(0.0).is_err());
+    }
+    #[test]
+    fn mean_latent_needs_same_layout() {
+        let population = vec![DNA::new(2, 2), DNA::new(2, 3)];
+        assert!(DNA::mean_latent(&population).is_none());
+        assert!(DNA::mean_latent(&[]).is_none());
+        let dna1 = DNA::from_latent_vec(&[1.0, 1.0, 3.0], 2, Influence::Included).unwrap();
+        let dna2 = DNA::from_latent_vec(&[0.0, 3.0, -1.0], 2, Influence::Included).unwrap();
+        assert_eq!(DNA::mean_latent(&[dna1, dna2]), Some(vec![2.0, 1.0]));
+    }
+    #[test]
+    fn mix_takes_ranges_from_donors() {
+        let donors = [DNA::new(4, 2), DNA::new(4, 2), DNA::new(4, 2)];
+        let mixing: Mixing = "0..1=2, 1..3=0, 3..4=1".parse().unwrap();
+        let mixed = DNA::mix(&[&donors[0], &donors[1], &donors[2]], &mixing).unwrap();
+        for (i, donor) in [2, 0, 0, 1].iter().enumerate() {
+            assert!(mixed.genes[i] == donors[*donor].genes[i]);
+        }
+        assert!(DNA::mix(&[], &mixing).is_err());
+        assert!(DNA::mix(&[&donors[0], &DNA::new(4, 3)], &mixing).is_err());
+        let conditional = DNA::new_conditional(4, 2, &[2]);
+        assert!(DNA::mix(&[&donors[0], &conditional], &mixing).is_err());
+    }
+    #[test]
+    fn projection_survives_round_trip() {
+        let dna = DNA::new_conditional(3, 2, &[4]);
+        let larger = Layout {
+            pool_size: 4,
+            gene_size: 5,
+        };
+        let padded = dna.project(larger, &Projection::Resize).unwrap();
+        assert_eq!(padded.genes[3].get_influence(), 0.0);
+        assert_eq!(padded.conditions, dna.conditions);
+        let restored = padded
+            .project(dna.get_layout(), &Projection::Resize)
+            .unwrap();
+        assert_eq!(restored.to_string(), dna.to_string());
+        let rechunked = Layout {
+            pool_size: 1,
+            gene_size: 6,
+        };
+        let restored = dna
+            .project(rechunked, &Projection::Rechunk)
+            .unwrap()
+            .project(dna.get_layout(), &Projection::Rechunk)
+            .unwrap();
+        assert_eq!(
+            restored.to_latent_vec_with(LatentMode::Markers),
+            dna.to_latent_vec_with(LatentMode::Markers)
+        );
+    }
+    #[test]
+    fn projection_by_matrix() {
+        let dna = DNA::from_latent_vec(&[1.0, 2.0, 3.0, 4.0], 2, Influence::Default(0.5)).unwrap();
+        let layout = Layout {
+            pool_size: 1,
+            gene_size: 2,
+        };
+        let sum = Projection::Matrix(vec![1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+        let projected = dna.project(layout, &sum).unwrap();
+        assert_eq!(projected.to_latent_vec(), vec![0.5, 3.0, 7.0]);
+        let wrong = Projection::Matrix(vec![1.0; 4]);
+        assert_eq!(
+            dna.project(layout, &wrong).err(),
+            Some(Error::LengthMismatch {
+                expected: 8,
+                found: 4
+            })
+        );
+        let empty = Layout {
+            pool_size: 1,
+            gene_size: 0,
+        };
+        assert!(dna.project(empty, &Projection::Resize).is_err());
+    }
+    #[test]
+    fn model_is_converted_and_back() {
+        let mut dna = DNA::new_conditional(2, 2, &[3]);
+        dna.model = Some(Model::new("faces-v2", dna.get_layout()).unwrap());
+        let restored: DNA = dna.to_string().parse().unwrap();
+        assert_eq!(restored.model, dna.model);
+        assert!(DNA::is_valid(dna.to_string()));
+        let restored = DNA::from_bytes(&dna.to_bytes()).unwrap();
+        assert_eq!(restored.model, dna.model);
+    }
+    #[test]
+    fn models_cannot_be_merged() {
+        let mut dna1 = DNA::new(2, 2);
+        let mut dna2 = DNA::new(2, 2);
+        dna1.model = Some(Model::new("faces-v2", dna1.get_layout()).unwrap());
+        let merged = DNA::merge(dna1.clone(), dna2.clone(), false).unwrap();
+        assert_eq!(merged.model, dna1.model);
+        dna2.model = Some(Model::new("faces-v3", dna2.get_layout()).unwrap());
+        assert!(DNA::merge(dna1.clone(), dna2.clone(), false).is_none());
+        assert!(DNA::interpolate(&dna1, &dna2, 0.5, Interpolation::Linear).is_none());
+        let mixing: Mixing = "0..1=0, 1..2=1".parse().unwrap();
+        assert_eq!(
+            DNA::mix(&[&dna1, &dna2], &mixing).err(),
+            Some(Error::InvalidSetting(
+                "donors need the same layout, compatible conditions and model"
+            ))
+        );
+        let projected = dna1
+            .project(dna1.get_layout(), &Projection::Resize)
+            .unwrap();
+        assert!(projected.model.is_none());
+    }
 }
@@ -0,0 +1,123 @@
+use super::section::{self, Section};
+use super::DNA;
+use crate::error::Error;
+use crate::gene::Gene;
+use crate::utils::{self, Reader};
+
+/// Write DNA as `varint pool_size, varint gene_size, varint section count, sections, markers, crc32`.
+///
+/// Sections are `tag, varint length, payload`, unknown tags are skipped when reading. Markers are little endian in the DNA encoding,
+/// `U4` markers are packed two per byte with the first one in the low nibble.
+pub fn to_bytes(dna: &DNA) -> Vec<u8> {
+    let mut data = write(dna);
+    let check_sum = utils::crc32(&data);
+    data.extend_from_slice(&check_sum.to_le_bytes());
+    data
+}
+
+pub fn from_bytes(data: &[u8]) -> Result<DNA, Error> {
+    if data.len() < 4 {
+        return Err(Error::Malformed("missing integrity trailer"));
+    }
+    let (body, trailer) = data.split_at(data.len() - 4);
+    if utils::crc32(body).to_le_bytes() != trailer {
+        return Err(Error::ChecksumMismatch);
+    }
+    let (dna, length) = read(body)?;
+    if length != body.len() {
+        return Err(Error::LengthMismatch {
+            expected: length,
+            found: body.len(),
+        });
+    }
+    Ok(dna)
+}
+
+/// Write the DNA without the integrity trailer
+pub fn write(dna: &DNA) -> Vec<u8> {
+    let mut data = Vec::new();
+    utils::write_varint(&mut data, dna.pool_size as u64);
+    utils::write_varint(&mut data, dna.gene_size as u64);
+    let sections = dna.sections();
+    utils::write_varint(&mut data, sections.len() as u64);
+    for section in sections.iter() {
+        let payload = section.payload();
+        data.push(section.tag());
+        utils::write_varint(&mut data, payload.len() as u64);
+        data.extend_from_slice(&payload);
+    }
+    let encoding = dna.encoding;
+    let markers = dna
+        .genes
+        .iter()
+        .flat_map(|g| g.markers.iter())
+        .map(|m| encoding.encode(m.value))
+        .collect::<Vec<u32>>();
+    match encoding.marker_bits() {
+        4 => data.extend(
+            markers
+                .chunks(2)
+                .map(|pair| (pair[0] | pair.get(1).unwrap_or(&0) << 4) as u8),
+        ),
+        bits => {
+            for marker in markers {
+                data.extend_from_slice(&marker.to_le_bytes()[..bits / 8]);
+            }
+        }
+    }
+    data
+}
+
+/// Read DNA without the integrity trailer, return it and the number of bytes read.
+///
+/// Bytes after the markers are left for the caller.
+pub fn read(body: &[u8]) -> Result<(DNA, usize), Error> {
+    let mut reader = Reader { data: body };
+    let pool_size = reader.read_u16()?;
+    let gene_size = reader.read_u16()?;
+    if gene_size == 0 {
+        return Err(Error::Malformed("gene size needs to be more than 0"));
+    }
+    let sections = (0..reader.read_varint()?)
+        .map(|_| {
+            let tag = reader.read_bytes(1)?[0];
+            let length = reader.read_varint()? as usize;
+            Section::parse(tag, reader.read_bytes(length)?)
+        })
+        .collect::<Result<Vec<Option<Section>>, Error>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<Section>>();
+    let encoding = section::get_encoding(&sections);
+    let bits = encoding.marker_bits();
+    let count = pool_size as usize * (gene_size as usize + 1);
+    let expected = (count * bits).div_ceil(8);
+    if reader.data.len() < expected {
+        return Err(Error::LengthMismatch {
+            expected,
+            found: reader.data.len(),
+        });
+    }
+    let markers = match bits {
+        4 => reader.data[..expected]
+            .iter()
+            .flat_map(|b| vec![(b & 0x0f) as u32, (b >> 4) as u32])
+            .take(count)
+            .collect::<Vec<u32>>(),
+        _ => reader.data[..expected]
+            .chunks(bits / 8)
+            .map(|c| {
+                let mut bytes = [0; 4];
+                bytes[..c.len()].copy_from_slice(c);
+                u32::from_le_bytes(bytes)
+            })
+            .collect(),
+    };
+    let genes = markers
+        .chunks(gene_size as usize + 1)
+        .map(|g| Gene::from(g.iter().map(|m| encoding.decode(*m)).collect::<Vec<f32>>()))
+        .collect();
+    let mut dna = DNA::from_genes(gene_size, genes);
+    dna.apply_sections(sections);
+    Ok((dna, body.len() - reader.data.len() + expected))
+}
@@ -0,0 +1,47 @@
+//! Standard normal markers derived from bytes, the same on every platform and crate version.
+//!
+//! Block `i` of the stream is `SHA-256(DOMAIN, input length, input, i)` with lengths and
+//! counters as u64 big endian. Every 8 bytes are a big endian u64, each pair goes through
+//! Box-Muller to give two normals, see `normal::box_muller`.
+use crate::normal;
+use sha2::{Digest, Sha256};
+
+/// Separates derived DNA from other uses of SHA-256, part of the stability guarantee
+const DOMAIN: &[u8] = b"genome derive v1";
+
+/// Get an endless stream of standard normal values derived from `input`
+pub fn standard_normals(input: &[u8]) -> impl Iterator<Item = f32> + '_ {
+    (0u64..).flat_map(move |counter| {
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN);
+        hasher.update((input.len() as u64).to_be_bytes());
+        hasher.update(input);
+        hasher.update(counter.to_be_bytes());
+        let block = hasher.finalize();
+        (0..2)
+            .flat_map(|pair| {
+                let word = |i: usize| {
+                    let mut bytes = [0; 8];
+                    bytes.copy_from_slice(&block[i * 8..i * 8 + 8]);
+                    u64::from_be_bytes(bytes) >> 11
+                };
+                normal::box_muller(word(pair * 2), word(pair * 2 + 1))
+            })
+            .collect::<Vec<f32>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn normals_look_standard() {
+        let values = standard_normals(b"distribution")
+            .take(20000)
+            .collect::<Vec<f32>>();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+        assert!(mean.abs() < 0.05);
+        assert!((variance - 1.0).abs() < 0.05);
+    }
+}
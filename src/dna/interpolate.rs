@@ -0,0 +1,59 @@
+/// Linear interpolation of two vectors of the same length
+pub fn lerp(left: &[f32], right: &[f32], t: f32) -> Vec<f32> {
+    left.iter()
+        .zip(right.iter())
+        .map(|(l, r)| l * (1.0 - t) + r * t)
+        .collect()
+}
+
+/// Spherical interpolation of two vectors of the same length, along the arc between them.
+///
+/// Falls back to `lerp` when either vector is zero or they point the same way.
+pub fn slerp(left: &[f32], right: &[f32], t: f32) -> Vec<f32> {
+    let dot = left
+        .iter()
+        .zip(right.iter())
+        .map(|(l, r)| *l as f64 * *r as f64)
+        .sum::<f64>();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(left) * norm(right);
+    if norms == 0.0 {
+        return lerp(left, right, t);
+    }
+    let omega = (dot / norms).clamp(-1.0, 1.0).acos();
+    let sin_omega = omega.sin();
+    if sin_omega.abs() < 1e-6 {
+        return lerp(left, right, t);
+    }
+    let t = t as f64;
+    let left_weight = ((1.0 - t) * omega).sin() / sin_omega;
+    let right_weight = (t * omega).sin() / sin_omega;
+    left.iter()
+        .zip(right.iter())
+        .map(|(l, r)| (left_weight * *l as f64 + right_weight * *r as f64) as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn endpoints_are_kept() {
+        let (left, right) = (vec![1.0, 0.0, 2.0], vec![0.0, 1.0, -1.0]);
+        for interpolate in [lerp, slerp].iter() {
+            assert_eq!(interpolate(&left, &right, 0.0), left);
+            let end = interpolate(&left, &right, 1.0);
+            assert!(end
+                .iter()
+                .zip(right.iter())
+                .all(|(e, r)| (e - r).abs() < 1e-6));
+        }
+    }
+    #[test]
+    fn slerp_keeps_norm_of_unit_vectors() {
+        let middle = slerp(&[1.0, 0.0], &[0.0, 1.0], 0.5);
+        assert!((middle[0] - 0.5f32.sqrt()).abs() < 1e-6);
+        assert!((middle[1] - 0.5f32.sqrt()).abs() < 1e-6);
+        assert_eq!(slerp(&[1.0, 1.0], &[2.0, 2.0], 0.5), vec![1.5, 1.5]);
+    }
+}
@@ -0,0 +1,68 @@
+use super::Layout;
+
+/// Keep every marker at its gene and position, cut what does not fit and pad with zeros
+pub fn resize(markers: &[f32], from: Layout, to: Layout) -> Vec<f32> {
+    let from_gene_size = from.gene_size as usize;
+    (0..to.pool_size as usize)
+        .flat_map(|i| {
+            (0..to.gene_size as usize).map(move |j| {
+                match i < from.pool_size as usize && j < from_gene_size {
+                    true => markers[i * from_gene_size + j],
+                    false => 0.0,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Cut the latent to `len` markers or pad its end with zeros
+pub fn rechunk(markers: &[f32], len: usize) -> Vec<f32> {
+    let mut rechunked = markers[..len.min(markers.len())].to_vec();
+    rechunked.resize(len, 0.0);
+    rechunked
+}
+
+/// Multiply the latent by a row major matrix with one row per projected marker
+pub fn multiply(matrix: &[f32], markers: &[f32], rows: usize) -> Vec<f32> {
+    let columns = markers.len();
+    (0..rows)
+        .map(|r| {
+            matrix[r * columns..(r + 1) * columns]
+                .iter()
+                .zip(markers.iter())
+                .map(|(m, x)| m * x)
+                .sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn resize_keeps_positions() {
+        let from = Layout {
+            pool_size: 2,
+            gene_size: 2,
+        };
+        let to = Layout {
+            pool_size: 3,
+            gene_size: 1,
+        };
+        assert_eq!(resize(&[1.0, 2.0, 3.0, 4.0], from, to), vec![1.0, 3.0, 0.0]);
+        let padded = Layout {
+            pool_size: 3,
+            gene_size: 3,
+        };
+        let markers = resize(&[1.0, 2.0, 3.0, 4.0], from, padded);
+        assert_eq!(resize(&markers, padded, from), vec![1.0, 2.0, 3.0, 4.0]);
+    }
+    #[test]
+    fn multiply_by_matrix() {
+        let matrix = [1.0, 0.0, 0.0, 0.0, 2.0, 1.0];
+        assert_eq!(multiply(&matrix, &[3.0, 1.0, -1.0], 2), vec![3.0, 1.0]);
+        assert_eq!(multiply(&[], &[], 2), vec![0.0, 0.0]);
+        assert_eq!(rechunk(&[1.0, 2.0, 3.0], 2), vec![1.0, 2.0]);
+        assert_eq!(rechunk(&[1.0], 2), vec![1.0, 0.0]);
+    }
+}
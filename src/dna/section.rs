@@ -0,0 +1,114 @@
+use crate::condition::Condition;
+use crate::encoding::Encoding;
+use crate::error::Error;
+use crate::model::Model;
+use crate::utils;
+use std::convert::TryFrom;
+
+const CONDITIONS: u8 = 0x01;
+const ENCODING: u8 = 0x02;
+const MODEL: u8 = 0x03;
+
+/// The most conditions whose 4 byte payloads fit in the u16 section length
+pub const MAX_CONDITIONS: usize = u16::MAX as usize / 4;
+
+/// Optional data carried in the extended DNA header, stored as tag, byte length and payload
+pub enum Section {
+    Conditions(Vec<Condition>),
+    Encoding(Encoding),
+    Model(Model),
+}
+
+impl Section {
+    pub fn tag(&self) -> u8 {
+        match self {
+            Section::Conditions(_) => CONDITIONS,
+            Section::Encoding(_) => ENCODING,
+            Section::Model(_) => MODEL,
+        }
+    }
+    pub fn payload(&self) -> Vec<u8> {
+        match self {
+            Section::Conditions(conditions) => conditions
+                .iter()
+                .flat_map(|c| c.to_bytes().to_vec())
+                .collect(),
+            Section::Encoding(encoding) => encoding.to_bytes(),
+            Section::Model(model) => model.to_bytes(),
+        }
+    }
+    /// Parse a section, `None` for tags this version does not know so they can be skipped
+    pub fn parse(tag: u8, payload: &[u8]) -> Result<Option<Section>, Error> {
+        let section = match tag {
+            CONDITIONS if payload.len() % 4 != 0 => None,
+            CONDITIONS => {
+                let conditions = payload
+                    .chunks(4)
+                    .map(|c| Condition {
+                        num_classes: u16::from_be_bytes([c[0], c[1]]),
+                        class_id: u16::from_be_bytes([c[2], c[3]]),
+                    })
+                    .collect::<Vec<Condition>>();
+                match conditions.iter().all(|c| c.class_id < c.num_classes) {
+                    true => Some(Section::Conditions(conditions)),
+                    false => None,
+                }
+            }
+            ENCODING => return Encoding::from_bytes(payload).map(|e| Some(Section::Encoding(e))),
+            MODEL => Model::from_bytes(payload).map(Section::Model),
+            _ => return Ok(None),
+        };
+        section.map(Some).ok_or(Error::Malformed("invalid section"))
+    }
+}
+
+/// Write sections as hex, `tag(2) length(4) payload`
+pub fn to_string(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|s| {
+            let payload = s.payload();
+            // `DNA::set_conditions` and `Model::new` keep every payload within a u16 length
+            let length =
+                u16::try_from(payload.len()).expect("Section needs to fit in a u16 length");
+            format!(
+                "{}{}{}",
+                utils::u8_to_string(s.tag()),
+                utils::u16_to_string(length),
+                utils::bytes_to_string(&payload)
+            )
+        })
+        .collect()
+}
+
+/// Read `count` sections from hex, return the known ones with the number of chars consumed
+pub fn from_str(data: &str, count: u8) -> Result<(Vec<Section>, usize), Error> {
+    let mut cursor = 0;
+    let sections = (0..count)
+        .map(|_| {
+            if data.len() < cursor + 6 {
+                return Err(Error::Malformed("unexpected end of sections"));
+            }
+            let tag = utils::u8_from_str(&data[cursor..cursor + 2]);
+            let length = utils::u16_from_str(&data[cursor + 2..cursor + 6]) as usize;
+            if data.len() < cursor + 6 + length * 2 {
+                return Err(Error::Malformed("unexpected end of sections"));
+            }
+            let payload = utils::bytes_from_str(&data[cursor + 6..cursor + 6 + length * 2]);
+            cursor += 6 + length * 2;
+            Section::parse(tag, &payload)
+        })
+        .collect::<Result<Vec<Option<Section>>, Error>>()?;
+    Ok((sections.into_iter().flatten().collect(), cursor))
+}
+
+/// Get the marker encoding declared in sections, `F32` if there is none
+pub fn get_encoding(sections: &[Section]) -> Encoding {
+    sections
+        .iter()
+        .find_map(|s| match s {
+            Section::Encoding(encoding) => Some(*encoding),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,336 @@
+use crate::error::Error;
+
+/// How markers are written in the DNA string, selected in the DNA header.
+///
+/// Maximum error of a decoded marker versus the f32 latent:
+///
+/// | Encoding     | Hex chars | Maximum error                                        |
+/// |--------------|-----------|------------------------------------------------------|
+/// | `F32`        | 8         | lossless                                             |
+/// | `F16`        | 4         | relative 2^-11 for \|x\| in [6.1e-5, 65504], absolute 2^-25 below, infinity above 65504 |
+/// | `BF16`       | 4         | relative 2^-8                                        |
+/// | `U8(scale)`  | 2         | absolute `scale / 255` for \|x\| <= `scale`, clipped above |
+/// | `U4(scale)`  | 1         | absolute `scale / 15` for \|x\| <= `scale`, clipped above  |
+///
+/// The scale of `U8` and `U4` needs to be finite and more than 0, see `Encoding::validate`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Encoding {
+    /// Full precision float, the legacy encoding
+    #[default]
+    F32,
+    /// IEEE 754 half precision float
+    F16,
+    /// Brain float, f32 with the lower 16 bits of mantissa rounded off
+    BF16,
+    /// 256 levels spread evenly over `-scale..=scale`
+    U8(f32),
+    /// 16 levels spread evenly over `-scale..=scale`
+    U4(f32),
+}
+
+impl Encoding {
+    /// Check the scale of a quantized encoding is finite and more than 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert!(Encoding::U8(3.0).validate().is_ok());
+    /// assert!(Encoding::U4(0.0).validate().is_err());
+    /// assert!(Encoding::U8(f32::NAN).validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            Encoding::U8(scale) | Encoding::U4(scale) if !(scale.is_finite() && *scale > 0.0) => {
+                Err(Error::InvalidSetting("scale needs to be a positive number"))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Get the number of hex chars used for each marker
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert_eq!(Encoding::F16.marker_width(), 4);
+    /// ```
+    pub fn marker_width(&self) -> usize {
+        self.marker_bits() / 4
+    }
+    /// Get the number of bits used for each marker
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert_eq!(Encoding::U4(3.0).marker_bits(), 4);
+    /// ```
+    pub fn marker_bits(&self) -> usize {
+        match self {
+            Encoding::F32 => 32,
+            Encoding::F16 | Encoding::BF16 => 16,
+            Encoding::U8(_) => 8,
+            Encoding::U4(_) => 4,
+        }
+    }
+    /// Encode a marker into its raw bits, stored in the low `marker_bits`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert_eq!(Encoding::F16.encode(1.0), 0x3c00);
+    /// ```
+    pub fn encode(&self, value: f32) -> u32 {
+        match self {
+            Encoding::F32 => value.to_bits(),
+            Encoding::F16 => f32_to_f16_bits(value) as u32,
+            Encoding::BF16 => f32_to_bf16_bits(value) as u32,
+            Encoding::U8(scale) => quantize_level(value, *scale, 255),
+            Encoding::U4(scale) => quantize_level(value, *scale, 15),
+        }
+    }
+    /// Decode raw bits back into a marker
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert_eq!(Encoding::F16.decode(0x3c00), 1.0);
+    /// ```
+    pub fn decode(&self, bits: u32) -> f32 {
+        match self {
+            Encoding::F32 => f32::from_bits(bits),
+            Encoding::F16 => f16_bits_to_f32(bits as u16),
+            Encoding::BF16 => f32::from_bits(bits << 16),
+            Encoding::U8(scale) => dequantize_level(bits, *scale, 255),
+            Encoding::U4(scale) => dequantize_level(bits, *scale, 15),
+        }
+    }
+    /// Round a marker to the closest value this encoding can hold
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// let encoding = Encoding::U8(1.0);
+    ///
+    /// assert!((encoding.quantize(0.3) - 0.3).abs() <= 1.0 / 255.0);
+    /// ```
+    pub fn quantize(&self, value: f32) -> f32 {
+        self.decode(self.encode(value))
+    }
+    /// Convert a marker to string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert_eq!(Encoding::BF16.marker_to_string(1.0), "3f80");
+    /// ```
+    pub fn marker_to_string(&self, value: f32) -> String {
+        format!(
+            "{:0>width$x}",
+            self.encode(value),
+            width = self.marker_width()
+        )
+    }
+    /// Convert string to marker
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Encoding;
+    ///
+    /// assert_eq!(Encoding::BF16.marker_from_str("3f80"), 1.0);
+    /// ```
+    pub fn marker_from_str(&self, data: &str) -> f32 {
+        self.decode(u32::from_str_radix(data, 16).unwrap())
+    }
+    /// Convert encoding to header bytes, the kind followed by the scale for quantized kinds
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        match self {
+            Encoding::F32 => vec![0],
+            Encoding::F16 => vec![1],
+            Encoding::BF16 => vec![2],
+            Encoding::U8(scale) => [&[3], &scale.to_be_bytes()[..]].concat(),
+            Encoding::U4(scale) => [&[4], &scale.to_be_bytes()[..]].concat(),
+        }
+    }
+    /// Convert header bytes to encoding, the scale of quantized kinds is validated
+    pub(crate) fn from_bytes(data: &[u8]) -> Result<Encoding, Error> {
+        let scale = || {
+            if data.len() != 5 {
+                return None;
+            }
+            Some(f32::from_be_bytes([data[1], data[2], data[3], data[4]]))
+        };
+        let encoding = match data.first() {
+            Some(0) => Some(Encoding::F32),
+            Some(1) => Some(Encoding::F16),
+            Some(2) => Some(Encoding::BF16),
+            Some(3) => scale().map(Encoding::U8),
+            Some(4) => scale().map(Encoding::U4),
+            _ => None,
+        }
+        .ok_or(Error::Malformed("invalid encoding section"))?;
+        encoding.validate()?;
+        Ok(encoding)
+    }
+}
+
+pub(crate) fn quantize_level(value: f32, scale: f32, levels: u32) -> u32 {
+    let clamped = value.max(-scale).min(scale);
+    ((clamped + scale) / (2.0 * scale) * levels as f32).round() as u32
+}
+
+pub(crate) fn dequantize_level(level: u32, scale: f32, levels: u32) -> f32 {
+    level as f32 / levels as f32 * 2.0 * scale - scale
+}
+
+fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        // Infinity stays infinity, NaN keeps a quiet bit
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    let (half, remainder, halfway) = if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        // Subnormal half, shift the implicit bit into the mantissa
+        let full = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        (full >> shift, full & ((1 << shift) - 1), 1 << (shift - 1))
+    } else {
+        (
+            ((half_exponent as u32) << 10) | (mantissa >> 13),
+            mantissa & 0x1fff,
+            0x1000,
+        )
+    };
+    // Round to nearest even, a carry into the exponent is still correct
+    if remainder > halfway || (remainder == halfway && half & 1 == 1) {
+        sign | (half + 1) as u16
+    } else {
+        sign | half as u16
+    }
+}
+
+fn f16_bits_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exponent = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+    if exponent == 0 {
+        let value = mantissa as f32 * 2f32.powi(-24);
+        return if sign == 0 { value } else { -value };
+    }
+    if exponent == 0x1f {
+        return f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13));
+    }
+    f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13))
+}
+
+fn f32_to_bf16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        return ((bits >> 16) | 0x40) as u16;
+    }
+    let rounding = 0x7fff + ((bits >> 16) & 1);
+    (bits.wrapping_add(rounding) >> 16) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand_distr::StandardNormal;
+    fn markers() -> Vec<f32> {
+        (0..4096)
+            .map(|_| thread_rng().sample(StandardNormal))
+            .collect()
+    }
+    #[test]
+    fn f32_is_lossless() {
+        for value in markers() {
+            assert_eq!(Encoding::F32.quantize(value), value);
+        }
+    }
+    #[test]
+    fn f16_within_error() {
+        for value in markers() {
+            let error = (Encoding::F16.quantize(value) - value).abs();
+            assert!(error <= value.abs() * 2f32.powi(-11) + 2f32.powi(-25));
+        }
+        assert_eq!(f32_to_f16_bits(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16_bits(1e6), 0x7c00);
+        assert_eq!(f16_bits_to_f32(0x0001), 2f32.powi(-24));
+        assert_eq!(f16_bits_to_f32(f32_to_f16_bits(-2.5)), -2.5);
+    }
+    #[test]
+    fn bf16_within_error() {
+        for value in markers() {
+            let error = (Encoding::BF16.quantize(value) - value).abs();
+            assert!(error <= value.abs() * 2f32.powi(-8));
+        }
+    }
+    #[test]
+    fn quantized_within_error() {
+        for value in markers() {
+            let clamped = value.clamp(-3.0, 3.0);
+            let error = (Encoding::U8(3.0).quantize(value) - clamped).abs();
+            assert!(error <= 3.0 / 255.0 + 1e-6);
+            let error = (Encoding::U4(3.0).quantize(value) - clamped).abs();
+            assert!(error <= 3.0 / 15.0 + 1e-6);
+        }
+    }
+    #[test]
+    fn quantize_is_idempotent() {
+        for encoding in [
+            Encoding::F16,
+            Encoding::BF16,
+            Encoding::U8(2.5),
+            Encoding::U4(2.5),
+        ]
+        .iter()
+        {
+            for value in markers() {
+                let quantized = encoding.quantize(value);
+                assert_eq!(encoding.quantize(quantized), quantized);
+            }
+        }
+    }
+    #[test]
+    fn header_bytes_round_trip() {
+        for encoding in [
+            Encoding::F32,
+            Encoding::F16,
+            Encoding::BF16,
+            Encoding::U8(2.5),
+            Encoding::U4(1.5),
+        ]
+        .iter()
+        {
+            assert_eq!(Encoding::from_bytes(&encoding.to_bytes()), Ok(*encoding));
+        }
+        assert!(Encoding::from_bytes(&Encoding::U4(-1.5).to_bytes()).is_err());
+        assert!(Encoding::from_bytes(&[3, 0]).is_err());
+    }
+}
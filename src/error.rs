@@ -0,0 +1,60 @@
+use std::fmt;
+
+/// Errors returned when decoding or converting a `DNA` fails
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// A setting is outside of its supported range
+    InvalidSetting(&'static str),
+    /// The encoded data is not in the expected format
+    Malformed(&'static str),
+    /// The integrity check of the encoded data failed
+    ChecksumMismatch,
+    /// The char at `position` is not part of the alphabet
+    InvalidCharacter { position: usize },
+    /// More symbols are wrong than the error correction can fix
+    Uncorrectable,
+    /// The checksum failed, changing any one of the chars at `positions` could fix it
+    Typo { positions: Vec<usize> },
+    /// The word at `position` is not in the wordlist
+    UnknownWord { position: usize },
+    /// The encoded data needs `found` units but only `limit` are allowed
+    TooLarge { limit: usize, found: usize },
+    /// The signature was not made by the key
+    InvalidSignature,
+    /// The data was signed by a different key
+    UnknownKey,
+    /// A proof or transcript did not check out
+    VerificationFailed(&'static str),
+    /// The input holds a different amount of data than the layout needs
+    LengthMismatch { expected: usize, found: usize },
+    /// The `DNA` was made for a different model or layout
+    ModelMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidSetting(reason) => write!(f, "invalid setting: {}", reason),
+            Error::Malformed(reason) => write!(f, "malformed data: {}", reason),
+            Error::ChecksumMismatch => write!(f, "checksum mismatch"),
+            Error::Uncorrectable => write!(f, "too many errors to correct"),
+            Error::InvalidCharacter { position } => {
+                write!(f, "invalid character at position {}", position)
+            }
+            Error::Typo { positions } => write!(f, "likely typo at positions {:?}", positions),
+            Error::UnknownWord { position } => write!(f, "unknown word at position {}", position),
+            Error::TooLarge { limit, found } => {
+                write!(f, "needs {} units, more than the limit of {}", found, limit)
+            }
+            Error::InvalidSignature => write!(f, "invalid signature"),
+            Error::UnknownKey => write!(f, "signed by an unknown key"),
+            Error::VerificationFailed(reason) => write!(f, "verification failed: {}", reason),
+            Error::LengthMismatch { expected, found } => {
+                write!(f, "expected length {}, found {}", expected, found)
+            }
+            Error::ModelMismatch => write!(f, "made for a different model"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,10 +1,12 @@
 mod marker;
 mod mutation;
+use crate::uniform;
 use crate::utils;
 use marker::Marker;
 use mutation::MutationType;
 use rand::prelude::*;
 
+#[derive(Clone)]
 pub struct Gene {
     pub num_markers: u16,
     pub markers: Vec<Marker>,
@@ -37,13 +39,26 @@ impl Gene {
     /// let gene1 = Gene::new(2);
     /// ```
     pub fn new(num_markers: u16) -> Gene {
+        Gene::new_with_rng(num_markers, &mut thread_rng())
+    }
+    /// Create a new gene, drawing markers from `rng`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ChaCha20Rng, Gene};
+    /// use rand::SeedableRng;
+    ///
+    /// let gene1 = Gene::new_with_rng(2, &mut ChaCha20Rng::seed_from_u64(7));
+    /// ```
+    pub fn new_with_rng<R: Rng + ?Sized>(num_markers: u16, rng: &mut R) -> Gene {
         if num_markers < 1 {
             panic!("Markers needs to be more than 0");
         }
         Gene {
-            num_markers: num_markers,
+            num_markers,
             markers: (0..num_markers + 1)
-                .map(|_| marker::Marker::new())
+                .map(|_| marker::Marker::from_rng(rng))
                 .collect(),
         }
     }
@@ -187,28 +202,43 @@ impl Gene {
     /// gene1.mutate();
     /// ```
     pub fn mutate(&mut self) {
-        let mutation_type = mutation::get_mutation_type();
-        let target = thread_rng().gen_range(0, self.markers.len());
+        self.mutate_with_rng(&mut thread_rng());
+    }
+    /// Mutate this gene, drawing from `rng`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ChaCha20Rng, Gene};
+    /// use rand::SeedableRng;
+    ///
+    /// let mut gene1 = Gene::new(2);
+    ///
+    /// gene1.mutate_with_rng(&mut ChaCha20Rng::seed_from_u64(7));
+    /// ```
+    pub fn mutate_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let mutation_type = mutation::get_mutation_type(rng);
+        let target = uniform::range(rng, 0, self.markers.len());
         match mutation_type {
             MutationType::DELETE => self.set_marker(target, 0 as f32),
             MutationType::DUPLICATION => {
                 // exclude the influence marker, allow noop
-                let dup_target = thread_rng().gen_range(1, self.markers.len());
+                let dup_target = uniform::range(rng, 1, self.markers.len());
                 self.set_marker(dup_target, self.markers[target].value);
             }
             MutationType::NEW => {
-                let new_marker = Marker::new();
+                let new_marker = Marker::from_rng(rng);
                 self.set_marker(target, new_marker.value);
             }
             MutationType::REVERSAL => {
                 // exclude the influence marker, allow noop
-                let swap_target = thread_rng().gen_range(1, self.markers.len());
+                let swap_target = uniform::range(rng, 1, self.markers.len());
                 let swap_value = self.markers[swap_target].value;
                 self.set_marker(swap_target, self.markers[target].value);
                 self.set_marker(target, swap_value);
             }
             MutationType::SHIFT => {
-                self.markers.shuffle(&mut thread_rng());
+                uniform::shuffle(rng, &mut self.markers);
             }
         }
     }
@@ -277,6 +307,27 @@ impl std::convert::From<String> for Gene {
     }
 }
 
+/// Convert marker values to Gene, the first value is influence
+///
+/// # Examples
+///
+/// ```
+/// use genome::Gene;
+///
+/// let gene1 = Gene::from(vec![0.5, 1.0, -1.0]);
+///
+/// assert_eq!(gene1.get_influence(), 0.5);
+/// assert_eq!(gene1.get_markers(), vec![1.0, -1.0]);
+/// ```
+impl std::convert::From<Vec<f32>> for Gene {
+    fn from(values: Vec<f32>) -> Gene {
+        Gene {
+            num_markers: values.len().saturating_sub(1) as u16,
+            markers: values.into_iter().map(Marker::from).collect(),
+        }
+    }
+}
+
 impl PartialEq for Gene {
     fn eq(&self, other: &Self) -> bool {
         Gene::is_equal(self, other)
@@ -1,16 +1,24 @@
+use crate::normal;
 use crate::utils;
 use rand::prelude::*;
-use rand_distr::StandardNormal;
 
 #[derive(Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Marker {
     pub value: f32,
 }
 
 impl Marker {
     pub fn new() -> Marker {
+        Marker::from_rng(&mut thread_rng())
+    }
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Marker {
         Marker {
-            value: thread_rng().sample(StandardNormal),
+            value: normal::standard_normal(rng),
         }
     }
     pub fn to_string(&self) -> String {
@@ -1,3 +1,4 @@
+use crate::uniform;
 use rand::prelude::*;
 
 pub enum MutationType {
@@ -8,8 +9,8 @@ pub enum MutationType {
     NEW,
 }
 
-pub fn get_mutation_type() -> MutationType {
-    match thread_rng().gen_range(0, 5) {
+pub fn get_mutation_type<R: Rng + ?Sized>(rng: &mut R) -> MutationType {
+    match uniform::range(rng, 0, 5) {
         0 => MutationType::DELETE,
         1 => MutationType::REVERSAL,
         2 => MutationType::DUPLICATION,
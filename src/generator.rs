@@ -0,0 +1,155 @@
+use crate::dna::{LatentMode, Layout, DNA};
+use crate::error::Error;
+use crate::model::Model;
+use crate::normal;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// A backend mapping `DNA` to its phenotype, such as image bytes, a feature vector or a set of
+/// traits. Evolution and fitness code can depend on this instead of a concrete model.
+pub trait Generator {
+    /// What the generator makes from a `DNA`
+    type Phenotype;
+    /// The error of the backend, crate errors such as a model mismatch convert into it
+    type Error: From<Error>;
+
+    /// Generate the phenotype of one `DNA`
+    fn generate(&self, dna: &DNA) -> Result<Self::Phenotype, Self::Error>;
+
+    /// Generate the phenotypes of a population in order. Backends that run a batch at once,
+    /// such as a GPU model, should override this; the default calls `generate` for each.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Generator, MockGenerator, DNA};
+    ///
+    /// let population = (0..4).map(|_| DNA::new(2, 2)).collect::<Vec<DNA>>();
+    /// let generator = MockGenerator::new(population[0].get_layout(), 8, 42);
+    ///
+    /// let phenotypes = generator.generate_batch(&population).unwrap();
+    ///
+    /// assert_eq!(phenotypes.len(), 4);
+    /// ```
+    fn generate_batch(&self, population: &[DNA]) -> Result<Vec<Self::Phenotype>, Self::Error> {
+        population.iter().map(|dna| self.generate(dna)).collect()
+    }
+}
+
+/// A deterministic in-process generator for tests, maps the scaled latent to a feature vector
+/// by a fixed random projection. Close latents give close features.
+pub struct MockGenerator {
+    // The model the generator stands in for, `DNA` of other models are refused
+    pub model: Model,
+    // Number of values made for each `DNA`
+    features: usize,
+    // Row major projection, one row of latent weights per feature
+    weights: Vec<f32>,
+}
+
+impl MockGenerator {
+    /// Create a generator taking `layout` and making `features` values, the same seed always
+    /// gives the same projection
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Generator, MockGenerator, DNA};
+    ///
+    /// let dna = DNA::new(2, 2);
+    /// let generator = MockGenerator::new(dna.get_layout(), 8, 42);
+    ///
+    /// let features = generator.generate(&dna).unwrap();
+    ///
+    /// assert_eq!(features.len(), 8);
+    /// assert_eq!(features, generator.generate(&dna).unwrap());
+    /// ```
+    pub fn new(layout: Layout, features: usize, seed: u64) -> MockGenerator {
+        let dim = layout.pool_size as usize * layout.gene_size as usize;
+        let scale = 1.0 / (dim.max(1) as f32).sqrt();
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        MockGenerator {
+            model: Model::new("mock", layout).expect("the mock id fits a length byte"),
+            features,
+            weights: (0..features * dim)
+                .map(|_| normal::standard_normal(&mut rng) * scale)
+                .collect(),
+        }
+    }
+    /// Get the number of features made for each `DNA`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{MockGenerator, DNA};
+    ///
+    /// let generator = MockGenerator::new(DNA::new(2, 2).get_layout(), 8, 42);
+    ///
+    /// assert_eq!(generator.features(), 8);
+    /// ```
+    pub fn features(&self) -> usize {
+        self.features
+    }
+}
+
+impl Generator for MockGenerator {
+    type Phenotype = Vec<f32>;
+    type Error = Error;
+
+    fn generate(&self, dna: &DNA) -> Result<Vec<f32>, Error> {
+        dna.check_model(&self.model)?;
+        let latent = dna.to_latent_vec_with(LatentMode::Scaled);
+        if latent.is_empty() {
+            return Ok(vec![0.0; self.features]);
+        }
+        Ok(self
+            .weights
+            .chunks(latent.len())
+            .map(|row| row.iter().zip(latent.iter()).map(|(w, x)| w * x).sum())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn mock_is_deterministic() {
+        let layout = Layout {
+            pool_size: 4,
+            gene_size: 4,
+        };
+        let dna = DNA::from_seed(7, layout);
+        let features = MockGenerator::new(layout, 3, 1).generate(&dna).unwrap();
+        assert_eq!(
+            features,
+            MockGenerator::new(layout, 3, 1).generate(&dna).unwrap()
+        );
+        assert_ne!(
+            features,
+            MockGenerator::new(layout, 3, 2).generate(&dna).unwrap()
+        );
+    }
+    #[test]
+    fn batch_matches_single() {
+        let population = (0..6).map(|_| DNA::new(2, 3)).collect::<Vec<DNA>>();
+        let generator = MockGenerator::new(population[0].get_layout(), 5, 3);
+        let batch = generator.generate_batch(&population).unwrap();
+        for (features, dna) in batch.iter().zip(population.iter()) {
+            assert_eq!(*features, generator.generate(dna).unwrap());
+        }
+    }
+    #[test]
+    fn other_models_are_refused() {
+        let mut dna = DNA::new(2, 2);
+        let generator = MockGenerator::new(dna.get_layout(), 4, 0);
+        assert_eq!(
+            generator.generate(&DNA::new(2, 3)).err(),
+            Some(Error::ModelMismatch)
+        );
+        dna.model = Some(Model::new("faces-v2", dna.get_layout()).unwrap());
+        assert_eq!(generator.generate(&dna).err(), Some(Error::ModelMismatch));
+        dna.model = Some(generator.model.clone());
+        assert!(generator.generate(&dna).is_ok());
+    }
+}
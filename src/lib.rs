@@ -1,6 +1,64 @@
+// Style lints the original API and its tests predate, kept allowed rather than rewriting them
+#![allow(
+    clippy::assertions_on_constants,
+    clippy::assign_op_pattern,
+    clippy::inherent_to_string,
+    clippy::manual_map,
+    clippy::needless_lifetimes,
+    clippy::needless_return,
+    clippy::unnecessary_cast,
+    clippy::upper_case_acronyms,
+    clippy::wrong_self_convention
+)]
+
+mod base58;
+mod batch;
+mod bech32;
+mod breeding;
+#[cfg(feature = "certificate")]
+mod certificate;
+mod composite;
+mod condition;
+mod direction;
 mod dna;
+mod encoding;
+mod error;
 mod gene;
+mod generator;
+mod merkle;
+mod mixing;
+mod mnemonic;
+mod model;
+mod normal;
+mod packing;
+mod permutation;
+mod reed_solomon;
+#[cfg(feature = "serde")]
+pub mod serialize;
+mod truncation;
+mod uniform;
 mod utils;
 
-pub use crate::dna::DNA;
+pub use crate::batch::{Batch, TensorFormat};
+pub use crate::breeding::Transcript;
+#[cfg(feature = "certificate")]
+pub use crate::certificate::Certificate;
+pub use crate::composite::{CompositeDNA, PartSettings};
+pub use crate::condition::Condition;
+pub use crate::direction::Direction;
+pub use crate::dna::{Influence, Interpolation, LatentMode, Layout, Projection, DNA};
+pub use crate::encoding::Encoding;
+pub use crate::error::Error;
 pub use crate::gene::Gene;
+pub use crate::generator::{Generator, MockGenerator};
+pub use crate::merkle::{MerkleTree, Proof};
+pub use crate::mixing::Mixing;
+pub use crate::mnemonic::Mnemonic;
+pub use crate::model::Model;
+pub use crate::packing::{Packing, Word};
+pub use crate::permutation::{Crossover, Permutation};
+pub use crate::reed_solomon::{Correction, ReedSolomon};
+pub use crate::truncation::Truncation;
+#[cfg(feature = "certificate")]
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+pub use rand_chacha::ChaCha20Rng;
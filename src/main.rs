@@ -1,11 +1,9 @@
-mod dna;
-mod gene;
-mod utils;
+use genome::DNA;
 
 fn main() {
-    let dna = dna::DNA::new(1, 1);
+    let dna = DNA::new(1, 1);
     let dna_str = String::from(dna);
     println!("{}", dna_str);
-    let dna_copy = dna::DNA::from(dna_str);
+    let dna_copy = DNA::from(dna_str);
     println!("{}", dna_copy.to_string());
 }
@@ -0,0 +1,260 @@
+use crate::dna::DNA;
+use crate::encoding::Encoding;
+use crate::error::Error;
+use crate::gene::Gene;
+use sha2::{Digest, Sha256};
+
+/// Separates salts from other uses of SHA-256
+const SALT_DOMAIN: &[u8] = b"genome merkle salt v1";
+
+/// A Merkle tree over the encoded genes of a `DNA`, to reveal single genes against its root.
+///
+/// Leaves are `SHA-256(0x00, salt, index, encoding, gene)` with the gene written as in the DNA
+/// string, nodes are `SHA-256(0x01, left, right)`. A node without a sibling moves up a level
+/// unchanged. The root is `SHA-256(0x02, leaf count, top node)`, so a proof cannot claim a
+/// different number of genes. Every leaf has a salt derived from a secret, so hidden genes of a
+/// quantized DNA cannot be found by trying every value.
+pub struct MerkleTree {
+    encoding: Encoding,
+    salts: Vec<[u8; 32]>,
+    // Leaves first, the root last
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+/// Proves one gene is part of a `MerkleTree` root
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proof {
+    pub index: usize,
+    pub leaf_count: usize,
+    pub encoding: Encoding,
+    pub salt: [u8; 32],
+    // Sibling hashes from the leaf up, levels where the node has no sibling are skipped
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleTree {
+    /// Build a tree over the genes of a DNA, `secret` needs to stay private to hide the genes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{MerkleTree, DNA};
+    ///
+    /// let dna = DNA::new(4, 2);
+    ///
+    /// let tree = MerkleTree::new(&dna, b"secret");
+    /// ```
+    pub fn new(dna: &DNA, secret: &[u8]) -> MerkleTree {
+        let salts = (0..dna.genes.len())
+            .map(|i| {
+                let mut hasher = Sha256::new();
+                hasher.update(SALT_DOMAIN);
+                hasher.update((secret.len() as u64).to_be_bytes());
+                hasher.update(secret);
+                hasher.update((i as u64).to_be_bytes());
+                hasher.finalize().into()
+            })
+            .collect::<Vec<[u8; 32]>>();
+        let leaves = dna
+            .genes
+            .iter()
+            .zip(salts.iter())
+            .enumerate()
+            .map(|(i, (gene, salt))| hash_leaf(i, salt, dna.get_encoding(), gene))
+            .collect::<Vec<[u8; 32]>>();
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    _ => pair[0],
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree {
+            encoding: dna.get_encoding(),
+            salts,
+            levels,
+        }
+    }
+    /// Get the root to publish as the commitment, all zero for a DNA without genes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{MerkleTree, DNA};
+    ///
+    /// let dna = DNA::new(4, 2);
+    ///
+    /// let root = MerkleTree::new(&dna, b"secret").get_root();
+    /// ```
+    pub fn get_root(&self) -> [u8; 32] {
+        match self.levels.last().unwrap().first() {
+            Some(top) => hash_root(self.salts.len(), top),
+            None => [0; 32],
+        }
+    }
+    /// Get the inclusion proof of the gene at `index`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{MerkleTree, DNA};
+    ///
+    /// let dna = DNA::new(4, 2);
+    /// let tree = MerkleTree::new(&dna, b"secret");
+    ///
+    /// let proof = tree.prove(2).unwrap();
+    ///
+    /// assert!(proof.verify(&dna.genes[2], &tree.get_root()).is_ok());
+    /// ```
+    pub fn prove(&self, index: usize) -> Option<Proof> {
+        let salt = *self.salts.get(index)?;
+        let mut position = index;
+        let mut siblings = Vec::new();
+        for level in self.levels[..self.levels.len() - 1].iter() {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(Proof {
+            index,
+            leaf_count: self.salts.len(),
+            encoding: self.encoding,
+            salt,
+            siblings,
+        })
+    }
+}
+
+impl Proof {
+    /// Check a revealed gene against the root of the tree
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{MerkleTree, DNA};
+    ///
+    /// let dna = DNA::new(4, 2);
+    /// let tree = MerkleTree::new(&dna, b"secret");
+    /// let proof = tree.prove(2).unwrap();
+    ///
+    /// assert!(proof.verify(&dna.genes[1], &tree.get_root()).is_err());
+    /// ```
+    pub fn verify(&self, gene: &Gene, root: &[u8; 32]) -> Result<(), Error> {
+        if self.index >= self.leaf_count {
+            return Err(Error::Malformed(
+                "index needs to be less than the leaf count",
+            ));
+        }
+        let mut hash = hash_leaf(self.index, &self.salt, self.encoding, gene);
+        let mut siblings = self.siblings.iter();
+        let (mut position, mut width) = (self.index, self.leaf_count);
+        while width > 1 {
+            if position ^ 1 < width {
+                let sibling = siblings.next().ok_or(Error::Malformed("missing sibling"))?;
+                hash = match position & 1 {
+                    0 => hash_node(&hash, sibling),
+                    _ => hash_node(sibling, &hash),
+                };
+            }
+            position /= 2;
+            width = width.div_ceil(2);
+        }
+        if siblings.next().is_some() {
+            return Err(Error::Malformed("unexpected sibling"));
+        }
+        if hash_root(self.leaf_count, &hash) != *root {
+            return Err(Error::VerificationFailed("gene is not part of the root"));
+        }
+        Ok(())
+    }
+}
+
+fn hash_leaf(index: usize, salt: &[u8; 32], encoding: Encoding, gene: &Gene) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0]);
+    hasher.update(salt);
+    hasher.update((index as u64).to_be_bytes());
+    hasher.update(encoding.to_bytes());
+    for marker in gene.markers.iter() {
+        hasher.update(encoding.marker_to_string(marker.value));
+    }
+    hasher.finalize().into()
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([1]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn hash_root(leaf_count: usize, top: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([2]);
+    hasher.update((leaf_count as u64).to_be_bytes());
+    hasher.update(top);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn every_gene_can_be_proven() {
+        for pool_size in 1..12 {
+            let mut dna = DNA::new(pool_size, 3);
+            dna.set_encoding(Encoding::U4(3.0)).unwrap();
+            let tree = MerkleTree::new(&dna, b"secret");
+            for (i, gene) in dna.genes.iter().enumerate() {
+                let proof = tree.prove(i).unwrap();
+                assert!(proof.verify(gene, &tree.get_root()).is_ok());
+            }
+            assert!(tree.prove(pool_size as usize).is_none());
+        }
+    }
+    #[test]
+    fn altered_gene_is_rejected() {
+        let dna = DNA::new(5, 3);
+        let tree = MerkleTree::new(&dna, b"secret");
+        let proof = tree.prove(4).unwrap();
+        let mut gene = dna.genes[4].clone();
+        gene.markers[1].value += 0.5;
+        assert_eq!(
+            proof.verify(&gene, &tree.get_root()),
+            Err(Error::VerificationFailed("gene is not part of the root"))
+        );
+        let mut moved = proof.clone();
+        moved.index = 3;
+        assert!(moved.verify(&dna.genes[4], &tree.get_root()).is_err());
+    }
+    #[test]
+    fn leaf_count_is_bound() {
+        // The first leaf of 4 has the same siblings as in a tree of 3
+        let dna = DNA::new(4, 3);
+        let tree = MerkleTree::new(&dna, b"secret");
+        let mut proof = tree.prove(0).unwrap();
+        proof.leaf_count = 3;
+        assert_eq!(
+            proof.verify(&dna.genes[0], &tree.get_root()),
+            Err(Error::VerificationFailed("gene is not part of the root"))
+        );
+    }
+    #[test]
+    fn secret_changes_root() {
+        let dna = DNA::new(3, 3);
+        assert_ne!(
+            MerkleTree::new(&dna, b"secret").get_root(),
+            MerkleTree::new(&dna, b"other").get_root()
+        );
+        assert_eq!(MerkleTree::new(&DNA::new(0, 3), b"").get_root(), [0; 32]);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::error::Error;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Which donor each range of genes is taken from when mixing styles, coarse genes first.
+///
+/// The ranges need to be in order and cover every gene exactly once, written as
+/// `0..2=0, 2..4=1` for the first two genes from donor 0 and the rest from donor 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mixing {
+    pub ranges: Vec<(Range<u16>, usize)>,
+}
+
+impl Mixing {
+    /// Check the spec against a pool size and number of donors
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Mixing;
+    ///
+    /// let mixing: Mixing = "0..2=0, 2..4=1".parse().unwrap();
+    ///
+    /// assert!(mixing.validate(4, 2).is_ok());
+    /// assert!(mixing.validate(6, 2).is_err());
+    /// ```
+    pub fn validate(&self, pool_size: u16, donors: usize) -> Result<(), Error> {
+        let mut next = 0;
+        for (range, donor) in self.ranges.iter() {
+            if range.start != next || range.end <= range.start {
+                return Err(Error::InvalidSetting(
+                    "ranges need to be in order without gaps or overlaps",
+                ));
+            }
+            if *donor >= donors {
+                return Err(Error::InvalidSetting("donor index out of range"));
+            }
+            next = range.end;
+        }
+        if next != pool_size {
+            return Err(Error::LengthMismatch {
+                expected: pool_size as usize,
+                found: next as usize,
+            });
+        }
+        Ok(())
+    }
+    /// Get the donor of a gene
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Mixing;
+    ///
+    /// let mixing: Mixing = "0..2=0, 2..4=1".parse().unwrap();
+    ///
+    /// assert_eq!(mixing.get_donor(3), Some(1));
+    /// ```
+    pub fn get_donor(&self, gene: u16) -> Option<usize> {
+        self.ranges
+            .iter()
+            .find(|(range, _)| range.contains(&gene))
+            .map(|(_, donor)| *donor)
+    }
+    /// Convert spec to string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Mixing;
+    ///
+    /// let mixing = Mixing {
+    ///     ranges: vec![(0..1, 1), (1..4, 0)],
+    /// };
+    ///
+    /// assert_eq!(mixing.to_string(), "0..1=1, 1..4=0");
+    /// ```
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|(range, donor)| format!("{}..{}={}", range.start, range.end, donor))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl FromStr for Mixing {
+    type Err = Error;
+    fn from_str(data: &str) -> Result<Mixing, Error> {
+        let ranges = data
+            .split(',')
+            .map(|part| {
+                let malformed = Error::Malformed("expected start..end=donor");
+                let (range, donor) = part.trim().split_once('=').ok_or(malformed.clone())?;
+                let (start, end) = range.split_once("..").ok_or(malformed.clone())?;
+                match (start.parse(), end.parse(), donor.parse()) {
+                    (Ok(start), Ok(end), Ok(donor)) => Ok((start..end, donor)),
+                    _ => Err(malformed),
+                }
+            })
+            .collect::<Result<Vec<(Range<u16>, usize)>, Error>>()?;
+        Ok(Mixing { ranges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn can_be_converted_and_back() {
+        let mixing = Mixing {
+            ranges: vec![(0..3, 2), (3..4, 0), (4..16, 1)],
+        };
+        assert_eq!(mixing.to_string().parse::<Mixing>().unwrap(), mixing);
+        assert!("0..2".parse::<Mixing>().is_err());
+        assert!("0-2=1".parse::<Mixing>().is_err());
+        assert!("0..2=a".parse::<Mixing>().is_err());
+    }
+    #[test]
+    fn ranges_are_validated() {
+        let mixing: Mixing = "0..2=0, 1..4=1".parse().unwrap();
+        assert!(mixing.validate(4, 2).is_err());
+        let mixing: Mixing = "0..2=0, 3..4=1".parse().unwrap();
+        assert!(mixing.validate(4, 2).is_err());
+        let mixing: Mixing = "0..2=0, 2..2=1, 2..4=1".parse().unwrap();
+        assert!(mixing.validate(4, 2).is_err());
+        let mixing: Mixing = "0..2=0, 2..4=2".parse().unwrap();
+        assert!(mixing.validate(4, 2).is_err());
+        let mixing: Mixing = "0..2=0, 2..4=1".parse().unwrap();
+        assert_eq!(
+            mixing.validate(3, 2).err(),
+            Some(Error::LengthMismatch {
+                expected: 3,
+                found: 4
+            })
+        );
+        assert!(mixing.validate(4, 2).is_ok());
+    }
+}
@@ -0,0 +1,200 @@
+mod wordlist;
+use crate::dna::{binary, DNA};
+use crate::error::Error;
+use crate::utils;
+use wordlist::WORDS;
+
+/// Bits of checksum that are at least added after the DNA
+const MIN_CHECK_BITS: usize = 8;
+
+/// Writes a `DNA` as words of the BIP39 English wordlist, to be read aloud or written down.
+///
+/// Every word holds 11 bits of the binary DNA, followed by the top bits of its CRC-32 to fill
+/// the last word, at least 8 of them. Only small or quantized DNA fits in a handful of words,
+/// set `DNA::encoding` to `Encoding::U4` to get the shortest phrase.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mnemonic {
+    max_words: usize,
+}
+
+impl Mnemonic {
+    /// Create a new mnemonic encoding, phrases longer than `max_words` are refused
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Mnemonic;
+    ///
+    /// let mnemonic = Mnemonic::new(24).unwrap();
+    /// ```
+    pub fn new(max_words: usize) -> Result<Mnemonic, Error> {
+        if max_words == 0 {
+            return Err(Error::InvalidSetting("word budget needs to be more than 0"));
+        }
+        Ok(Mnemonic { max_words })
+    }
+    /// Get the number of words needed for a DNA
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Encoding, Mnemonic, DNA};
+    ///
+    /// let mnemonic = Mnemonic::new(24).unwrap();
+    /// let mut dna = DNA::new(2, 2);
+    /// dna.set_encoding(Encoding::U4(3.0)).unwrap();
+    ///
+    /// assert_eq!(mnemonic.word_count(&dna), 11);
+    /// ```
+    pub fn word_count(&self, dna: &DNA) -> usize {
+        word_count(binary::write(dna).len())
+    }
+    /// Convert DNA to words separated by spaces
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Encoding, Mnemonic, DNA};
+    ///
+    /// let mnemonic = Mnemonic::new(24).unwrap();
+    /// let mut dna = DNA::new(2, 2);
+    /// dna.set_encoding(Encoding::U4(3.0)).unwrap();
+    ///
+    /// let phrase = mnemonic.encode(&dna).unwrap();
+    ///
+    /// assert!(mnemonic.encode(&DNA::new(8, 8)).is_err());
+    /// ```
+    pub fn encode(&self, dna: &DNA) -> Result<String, Error> {
+        let data = binary::write(dna);
+        let count = word_count(data.len());
+        if count > self.max_words {
+            return Err(Error::TooLarge {
+                limit: self.max_words,
+                found: count,
+            });
+        }
+        let check_bits = count * 11 - data.len() * 8;
+        let check_sum = utils::crc32(&data) >> (32 - check_bits);
+        let mut bits = data
+            .iter()
+            .flat_map(|b| (0..8).rev().map(move |i| (*b as u32 >> i) & 1))
+            .chain((0..check_bits).rev().map(|i| (check_sum >> i) & 1));
+        Ok((0..count)
+            .map(|_| WORDS[(0..11).fold(0, |w, _| w << 1 | bits.next().unwrap()) as usize])
+            .collect::<Vec<&str>>()
+            .join(" "))
+    }
+    /// Convert words to DNA, words can be separated by any whitespace and are not case sensitive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Encoding, Mnemonic, DNA};
+    ///
+    /// let mnemonic = Mnemonic::new(24).unwrap();
+    /// let mut dna = DNA::new(2, 2);
+    /// dna.set_encoding(Encoding::U4(3.0)).unwrap();
+    /// let phrase = mnemonic.encode(&dna).unwrap();
+    ///
+    /// let dna_copy = mnemonic.decode(&phrase).unwrap();
+    ///
+    /// assert_eq!(dna_copy.to_string(), dna.to_string());
+    /// ```
+    pub fn decode(&self, phrase: &str) -> Result<DNA, Error> {
+        let indices = phrase
+            .split_whitespace()
+            .enumerate()
+            .map(|(position, word)| {
+                WORDS
+                    .binary_search(&word.to_lowercase().as_str())
+                    .map_err(|_| Error::UnknownWord { position })
+            })
+            .collect::<Result<Vec<usize>, Error>>()?;
+        if indices.len() > self.max_words {
+            return Err(Error::TooLarge {
+                limit: self.max_words,
+                found: indices.len(),
+            });
+        }
+        let bits = indices
+            .iter()
+            .flat_map(|i| (0..11).rev().map(move |b| (*i as u32 >> b) & 1))
+            .collect::<Vec<u32>>();
+        let data = bits
+            .chunks_exact(8)
+            .map(|c| c.iter().fold(0, |b, bit| b << 1 | *bit as u8))
+            .collect::<Vec<u8>>();
+        let (dna, length) = binary::read(&data)?;
+        let expected = word_count(length);
+        if expected != indices.len() {
+            return Err(Error::LengthMismatch {
+                expected,
+                found: indices.len(),
+            });
+        }
+        let check_bits = expected * 11 - length * 8;
+        let check_sum = bits[length * 8..].iter().fold(0, |c, bit| c << 1 | bit);
+        if check_sum != utils::crc32(&data[..length]) >> (32 - check_bits) {
+            return Err(Error::ChecksumMismatch);
+        }
+        Ok(dna)
+    }
+}
+
+fn word_count(length: usize) -> usize {
+    (length * 8 + MIN_CHECK_BITS).div_ceil(11)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::Encoding;
+    fn small_dna() -> DNA {
+        let mut dna = DNA::new_conditional(3, 2, &[4]);
+        dna.set_encoding(Encoding::U4(3.0)).unwrap();
+        dna
+    }
+    #[test]
+    fn can_be_encoded_and_back() {
+        let mnemonic = Mnemonic::new(24).unwrap();
+        for _ in 0..64 {
+            let dna = small_dna();
+            let phrase = mnemonic.encode(&dna).unwrap();
+            assert_eq!(phrase.split(' ').count(), mnemonic.word_count(&dna));
+            let dna_copy = mnemonic.decode(&phrase.to_uppercase()).unwrap();
+            assert_eq!(dna_copy.to_string(), dna.to_string());
+        }
+    }
+    #[test]
+    fn word_budget_is_enforced() {
+        let dna = small_dna();
+        let count = Mnemonic::new(24).unwrap().word_count(&dna);
+        let mnemonic = Mnemonic::new(count - 1).unwrap();
+        assert_eq!(
+            mnemonic.encode(&dna).err(),
+            Some(Error::TooLarge {
+                limit: count - 1,
+                found: count
+            })
+        );
+        assert!(Mnemonic::new(0).is_err());
+    }
+    #[test]
+    fn typos_are_rejected() {
+        let mnemonic = Mnemonic::new(24).unwrap();
+        let phrase = mnemonic.encode(&small_dna()).unwrap();
+        let mut words = phrase.split(' ').collect::<Vec<&str>>();
+        words[1] = "dnaa";
+        assert_eq!(
+            mnemonic.decode(&words.join(" ")).err(),
+            Some(Error::UnknownWord { position: 1 })
+        );
+        let mut words = phrase.split(' ').collect::<Vec<&str>>();
+        let last = WORDS.binary_search(words.last().unwrap()).unwrap();
+        *words.last_mut().unwrap() = WORDS[last ^ 1];
+        assert_eq!(
+            mnemonic.decode(&words.join(" ")).err(),
+            Some(Error::ChecksumMismatch)
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::dna::Layout;
+use crate::error::Error;
+use sha2::{Digest, Sha256};
+
+/// The generator a `DNA` is made for, stored in the extended header so a `DNA` is not fed to
+/// the wrong model
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ModelFields")
+)]
+pub struct Model {
+    /// Identifier of the generator, at most 255 bytes
+    id: String,
+    /// Hash of the layout the generator takes, from `Model::layout_hash`
+    layout_hash: [u8; 8],
+}
+
+/// The fields of a deserialized `Model`, checked before they become one
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ModelFields {
+    id: String,
+    layout_hash: [u8; 8],
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<ModelFields> for Model {
+    type Error = Error;
+
+    fn try_from(fields: ModelFields) -> Result<Model, Error> {
+        check_id(&fields.id)?;
+        Ok(Model {
+            id: fields.id,
+            layout_hash: fields.layout_hash,
+        })
+    }
+}
+
+impl Model {
+    /// Create the metadata of a generator taking `layout`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Layout, Model};
+    ///
+    /// let layout = Layout {
+    ///     pool_size: 16,
+    ///     gene_size: 32,
+    /// };
+    ///
+    /// let model = Model::new("faces-v2", layout).unwrap();
+    /// ```
+    pub fn new(id: &str, layout: Layout) -> Result<Model, Error> {
+        check_id(id)?;
+        Ok(Model {
+            id: String::from(id),
+            layout_hash: Model::layout_hash(layout),
+        })
+    }
+    /// Get the identifier of the generator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Model, DNA};
+    ///
+    /// let model = Model::new("faces-v2", DNA::new(2, 2).get_layout()).unwrap();
+    ///
+    /// assert_eq!(model.get_id(), "faces-v2");
+    /// ```
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    /// Get the hash of the layout the generator takes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Model, DNA};
+    ///
+    /// let dna = DNA::new(2, 2);
+    /// let model = Model::new("faces-v2", dna.get_layout()).unwrap();
+    ///
+    /// assert_eq!(model.get_layout_hash(), Model::layout_hash(dna.get_layout()));
+    /// ```
+    pub fn get_layout_hash(&self) -> [u8; 8] {
+        self.layout_hash
+    }
+    /// Get the hash of a layout, the first 8 bytes of its SHA-256
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Layout, Model, DNA};
+    ///
+    /// let dna = DNA::new(2, 2);
+    ///
+    /// let hash = Model::layout_hash(dna.get_layout());
+    /// ```
+    pub fn layout_hash(layout: Layout) -> [u8; 8] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"genome layout v1");
+        hasher.update(layout.pool_size.to_be_bytes());
+        hasher.update(layout.gene_size.to_be_bytes());
+        let mut hash = [0; 8];
+        hash.copy_from_slice(&hasher.finalize()[..8]);
+        hash
+    }
+    /// Check if the generator takes `layout`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Model, DNA};
+    ///
+    /// let dna = DNA::new(2, 2);
+    /// let model = Model::new("faces-v2", dna.get_layout()).unwrap();
+    ///
+    /// assert!(model.takes(dna.get_layout()));
+    /// ```
+    pub fn takes(&self, layout: Layout) -> bool {
+        self.layout_hash == Model::layout_hash(layout)
+    }
+    /// Convert model to header bytes, the id length, id and layout hash. The id is checked to
+    /// fit a length byte when the model is made.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        [
+            &[self.id.len() as u8],
+            self.id.as_bytes(),
+            &self.layout_hash[..],
+        ]
+        .concat()
+    }
+    /// Convert header bytes to model
+    pub(crate) fn from_bytes(data: &[u8]) -> Option<Model> {
+        let length = *data.first()? as usize;
+        if data.len() != 1 + length + 8 {
+            return None;
+        }
+        let id = String::from_utf8(data[1..1 + length].to_vec()).ok()?;
+        let mut layout_hash = [0; 8];
+        layout_hash.copy_from_slice(&data[1 + length..]);
+        Some(Model { id, layout_hash })
+    }
+}
+
+fn check_id(id: &str) -> Result<(), Error> {
+    match id.len() <= u8::MAX as usize {
+        true => Ok(()),
+        false => Err(Error::InvalidSetting(
+            "model id needs to be at most 255 bytes",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn header_bytes_round_trip() {
+        let layout = Layout {
+            pool_size: 4,
+            gene_size: 8,
+        };
+        let model = Model::new("faces-v2", layout).unwrap();
+        assert_eq!(Model::from_bytes(&model.to_bytes()), Some(model.clone()));
+        assert!(Model::from_bytes(&model.to_bytes()[1..]).is_none());
+        assert!(Model::from_bytes(&[]).is_none());
+        assert!(Model::new(&"x".repeat(256), layout).is_err());
+    }
+    #[test]
+    fn layout_hash_is_stable() {
+        let layout = Layout {
+            pool_size: 4,
+            gene_size: 8,
+        };
+        let other = Layout {
+            pool_size: 8,
+            gene_size: 4,
+        };
+        assert_ne!(Model::layout_hash(layout), Model::layout_hash(other));
+        assert_eq!(
+            Model::layout_hash(layout),
+            [0x68, 0xe5, 0x84, 0x1a, 0xbb, 0x07, 0x62, 0x69]
+        );
+    }
+}
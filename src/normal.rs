@@ -0,0 +1,108 @@
+//! Standard normal values from uniform integers, the same on every platform and crate version.
+//!
+//! `ln`, `sin` and `cos` are computed here with plain f64 arithmetic, which is exact IEEE 754,
+//! instead of the platform math library or the sampling code of `rand_distr`.
+use rand::RngCore;
+use std::f64::consts::{FRAC_PI_4, LN_2, SQRT_2};
+
+/// Draw a standard normal from two `u64` of `rng`
+pub fn standard_normal<R: RngCore + ?Sized>(rng: &mut R) -> f32 {
+    box_muller(rng.next_u64() >> 11, rng.next_u64() >> 11)[0]
+}
+
+/// Map two 53 bit integers to two independent standard normals
+pub fn box_muller(first: u64, second: u64) -> [f32; 2] {
+    let scale = 1.0 / (1u64 << 53) as f64;
+    // Shifted into (0, 1] so the logarithm stays finite
+    let radius_uniform = (first + 1) as f64 * scale;
+    let angle_uniform = second as f64 * scale;
+    let radius = (-2.0 * ln(radius_uniform)).sqrt();
+    let (sin, cos) = sin_cos_turns(angle_uniform);
+    [(radius * cos) as f32, (radius * sin) as f32]
+}
+
+/// Natural logarithm of a positive normal number
+fn ln(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if mantissa > SQRT_2 {
+        mantissa /= 2.0;
+        exponent += 1;
+    }
+    // ln(m) = 2 atanh(s), |s| <= 0.172 so 14 terms are past f64 precision
+    let s = (mantissa - 1.0) / (mantissa + 1.0);
+    let s2 = s * s;
+    let series = (0..14)
+        .rev()
+        .fold(0.0, |sum, k| sum * s2 + 1.0 / (2 * k + 1) as f64);
+    2.0 * s * series + exponent as f64 * LN_2
+}
+
+/// Sine and cosine of `turns * 2 pi` for `turns` in `0..1`
+fn sin_cos_turns(turns: f64) -> (f64, f64) {
+    // Reduce to an eighth of a turn, the octant picks signs and swaps
+    let octants = turns * 8.0;
+    let octant = octants.floor() as u8 % 8;
+    let mut fraction = octants - octants.floor();
+    if octant % 2 == 1 {
+        fraction = 1.0 - fraction;
+    }
+    let x = fraction * FRAC_PI_4;
+    let x2 = x * x;
+    // Taylor series, |x| <= pi / 4 so 12 terms are past f64 precision
+    let (mut sin, mut cos) = (0.0, 0.0);
+    for k in (0..12).rev() {
+        let n = 2 * k as u32;
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        sin = sin * x2 + sign / factorial(n + 1);
+        cos = cos * x2 + sign / factorial(n);
+    }
+    sin *= x;
+    // Angle within the octant measured from the axis the octant starts or ends on
+    let (sin, cos) = match octant {
+        0 => (sin, cos),
+        1 => (cos, sin),
+        2 => (cos, -sin),
+        3 => (sin, -cos),
+        4 => (-sin, -cos),
+        5 => (-cos, -sin),
+        6 => (-cos, sin),
+        _ => (-sin, cos),
+    };
+    (sin, cos)
+}
+
+fn factorial(n: u32) -> f64 {
+    (1..=n).fold(1.0, |f, i| f * i as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    #[test]
+    fn ln_matches_std() {
+        for i in 1..1000 {
+            let x = i as f64 / 997.0;
+            assert!((ln(x) - x.ln()).abs() < 1e-15);
+        }
+        assert!((ln(2f64.powi(-53)) - 2f64.powi(-53).ln()).abs() < 1e-13);
+    }
+    #[test]
+    fn sin_cos_match_std() {
+        for i in 0..1000 {
+            let turns = i as f64 / 1000.0;
+            let (sin, cos) = sin_cos_turns(turns);
+            assert!((sin - (turns * 2.0 * PI).sin()).abs() < 1e-15);
+            assert!((cos - (turns * 2.0 * PI).cos()).abs() < 1e-15);
+        }
+    }
+    #[test]
+    fn box_muller_is_finite() {
+        let max = (1 << 53) - 1;
+        for (first, second) in [(0, 0), (max, max), (0, max), (max, 0)].iter() {
+            assert!(box_muller(*first, *second).iter().all(|v| v.is_finite()));
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use crate::dna::{Layout, DNA};
+use crate::encoding;
+use crate::error::Error;
+use crate::gene::Gene;
+use crate::utils;
+
+/// A 256-bit big endian word, the layout of a Solidity `uint256`
+pub type Word = [u8; 32];
+
+/// Quantizes a `DNA` into 256-bit words for on-chain storage.
+///
+/// Each gene is written as its influence followed by its markers, most significant bits first.
+/// A value never straddles two words, leftover bits at the end of a word are zero. Values are
+/// quantized over `-scale..=scale` like `Encoding::U8`, with a maximum error of
+/// `scale / (2^bits - 1)`. Conditions and encoding are not packed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Packing {
+    marker_bits: u8,
+    influence_bits: u8,
+    scale: f32,
+}
+
+impl Packing {
+    /// Create a new packing, bits need to be between 1 and 16
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Packing;
+    ///
+    /// let packing = Packing::new(8, 4, 3.0).unwrap();
+    /// ```
+    pub fn new(marker_bits: u8, influence_bits: u8, scale: f32) -> Result<Packing, Error> {
+        if !(1..=16).contains(&marker_bits) || !(1..=16).contains(&influence_bits) {
+            return Err(Error::InvalidSetting("bits need to be between 1 and 16"));
+        }
+        if scale.is_nan() || scale <= 0.0 || scale.is_infinite() {
+            return Err(Error::InvalidSetting("scale needs to be a positive number"));
+        }
+        Ok(Packing {
+            marker_bits,
+            influence_bits,
+            scale,
+        })
+    }
+    /// Get the number of words needed for a layout, the gene size needs to be more than 0
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Layout, Packing};
+    ///
+    /// let packing = Packing::new(8, 8, 3.0).unwrap();
+    ///
+    /// let count = packing.word_count(Layout { pool_size: 8, gene_size: 7 }).unwrap();
+    ///
+    /// assert_eq!(count, 2);
+    /// assert!(packing.word_count(Layout { pool_size: 8, gene_size: 0 }).is_err());
+    /// ```
+    pub fn word_count(&self, layout: Layout) -> Result<usize, Error> {
+        if layout.gene_size == 0 {
+            return Err(Error::InvalidSetting("gene size needs to be more than 0"));
+        }
+        let mut writer = BitWriter::new();
+        for _ in 0..layout.pool_size {
+            writer.skip(self.influence_bits);
+            for _ in 0..layout.gene_size {
+                writer.skip(self.marker_bits);
+            }
+        }
+        Ok(writer.finish().len())
+    }
+    /// Pack a `DNA` into words
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Packing, DNA};
+    ///
+    /// let packing = Packing::new(8, 8, 3.0).unwrap();
+    /// let dna = DNA::new(8, 7);
+    ///
+    /// let words = packing.pack(&dna);
+    ///
+    /// assert_eq!(words.len(), 2);
+    /// ```
+    pub fn pack(&self, dna: &DNA) -> Vec<Word> {
+        let mut writer = BitWriter::new();
+        for gene in dna.genes.iter() {
+            for (i, marker) in gene.markers.iter().enumerate() {
+                let bits = self.bits(i);
+                writer.write(
+                    encoding::quantize_level(marker.value, self.scale, levels(bits)),
+                    bits,
+                );
+            }
+        }
+        writer.finish()
+    }
+    /// Unpack words back into a `DNA`, markers are dequantized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Packing, DNA};
+    ///
+    /// let packing = Packing::new(8, 8, 3.0).unwrap();
+    /// let dna = DNA::new(8, 7);
+    /// let words = packing.pack(&dna);
+    ///
+    /// let unpacked = packing.unpack(&words, dna.get_layout()).unwrap();
+    /// ```
+    pub fn unpack(&self, words: &[Word], layout: Layout) -> Result<DNA, Error> {
+        let expected = self.word_count(layout)?;
+        if words.len() != expected {
+            return Err(Error::LengthMismatch {
+                expected,
+                found: words.len(),
+            });
+        }
+        let mut reader = BitReader::new(words);
+        let genes = (0..layout.pool_size)
+            .map(|_| {
+                Gene::from(
+                    (0..layout.gene_size as usize + 1)
+                        .map(|i| {
+                            let bits = self.bits(i);
+                            encoding::dequantize_level(reader.read(bits), self.scale, levels(bits))
+                        })
+                        .collect::<Vec<f32>>(),
+                )
+            })
+            .collect();
+        Ok(DNA::from_genes(layout.gene_size, genes))
+    }
+    /// Pack a `DNA` as the ABI encoding of a `uint256[]` function argument
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Packing, DNA};
+    ///
+    /// let packing = Packing::new(8, 8, 3.0).unwrap();
+    /// let dna = DNA::new(8, 7);
+    ///
+    /// let abi = packing.to_abi(&dna);
+    ///
+    /// // offset, length and two words
+    /// assert_eq!(abi.len(), 4 * 32);
+    /// ```
+    pub fn to_abi(&self, dna: &DNA) -> Vec<u8> {
+        let words = self.pack(dna);
+        let mut offset: Word = [0; 32];
+        offset[24..].copy_from_slice(&32u64.to_be_bytes());
+        let mut length: Word = [0; 32];
+        length[24..].copy_from_slice(&(words.len() as u64).to_be_bytes());
+        [vec![offset, length], words].concat().concat()
+    }
+    /// Unpack the ABI encoding of a `uint256[]` function argument into a `DNA`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Packing, DNA};
+    ///
+    /// let packing = Packing::new(8, 8, 3.0).unwrap();
+    /// let dna = DNA::new(8, 7);
+    /// let abi = packing.to_abi(&dna);
+    ///
+    /// let unpacked = packing.from_abi(&abi, dna.get_layout()).unwrap();
+    /// ```
+    pub fn from_abi(&self, data: &[u8], layout: Layout) -> Result<DNA, Error> {
+        let expected = (self.word_count(layout)? + 2) * 32;
+        if data.len() != expected {
+            return Err(Error::LengthMismatch {
+                expected,
+                found: data.len(),
+            });
+        }
+        let words = data
+            .chunks(32)
+            .map(|c| {
+                let mut word: Word = [0; 32];
+                word.copy_from_slice(c);
+                word
+            })
+            .collect::<Vec<Word>>();
+        if word_to_u64(&words[0]) != Some(32) {
+            return Err(Error::Malformed("array offset needs to be 32"));
+        }
+        if word_to_u64(&words[1]) != Some(words.len() as u64 - 2) {
+            return Err(Error::Malformed("array length does not match the layout"));
+        }
+        self.unpack(&words[2..], layout)
+    }
+    /// Pack a `DNA` as `0x` prefixed hex of the ABI encoding
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Packing, DNA};
+    ///
+    /// let packing = Packing::new(8, 8, 3.0).unwrap();
+    /// let dna = DNA::new(8, 7);
+    ///
+    /// let abi_hex = packing.to_abi_hex(&dna);
+    ///
+    /// assert!(abi_hex.starts_with("0x"));
+    /// ```
+    pub fn to_abi_hex(&self, dna: &DNA) -> String {
+        format!("0x{}", utils::bytes_to_string(&self.to_abi(dna)))
+    }
+    /// Get the word as `0x` prefixed hex, the form of a `uint256` literal
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Packing;
+    ///
+    /// let word_hex = Packing::word_to_hex(&[0; 32]);
+    ///
+    /// assert_eq!(word_hex.len(), 66);
+    /// ```
+    pub fn word_to_hex(word: &Word) -> String {
+        format!("0x{}", utils::bytes_to_string(word))
+    }
+    fn bits(&self, position: usize) -> u8 {
+        // First marker of each gene is influence
+        if position == 0 {
+            self.influence_bits
+        } else {
+            self.marker_bits
+        }
+    }
+}
+
+fn word_to_u64(word: &Word) -> Option<u64> {
+    if word[..24].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut low = [0; 8];
+    low.copy_from_slice(&word[24..]);
+    Some(u64::from_be_bytes(low))
+}
+
+fn levels(bits: u8) -> u32 {
+    (1 << bits) - 1
+}
+
+struct BitWriter {
+    words: Vec<Word>,
+    offset: usize,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            words: Vec::new(),
+            offset: 256,
+        }
+    }
+    fn skip(&mut self, bits: u8) {
+        self.write(0, bits);
+    }
+    fn write(&mut self, value: u32, bits: u8) {
+        if self.offset + bits as usize > 256 {
+            self.words.push([0; 32]);
+            self.offset = 0;
+        }
+        let word = self.words.last_mut().unwrap();
+        for i in 0..bits as usize {
+            if value >> (bits as usize - 1 - i) & 1 == 1 {
+                let position = self.offset + i;
+                word[position / 8] |= 0x80 >> (position % 8);
+            }
+        }
+        self.offset += bits as usize;
+    }
+    fn finish(self) -> Vec<Word> {
+        self.words
+    }
+}
+
+struct BitReader<'a> {
+    words: &'a [Word],
+    index: usize,
+    offset: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(words: &'a [Word]) -> BitReader<'a> {
+        BitReader {
+            words,
+            index: 0,
+            offset: 0,
+        }
+    }
+    fn read(&mut self, bits: u8) -> u32 {
+        if self.offset + bits as usize > 256 {
+            self.index += 1;
+            self.offset = 0;
+        }
+        let word = &self.words[self.index];
+        let mut value = 0;
+        for i in 0..bits as usize {
+            let position = self.offset + i;
+            value = value << 1 | (word[position / 8] >> (7 - position % 8) & 1) as u32;
+        }
+        self.offset += bits as usize;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn can_be_packed_and_back() {
+        let packing = Packing::new(8, 4, 3.0).unwrap();
+        let dna = DNA::new(16, 8);
+        let unpacked = packing
+            .unpack(&packing.pack(&dna), dna.get_layout())
+            .unwrap();
+        assert_eq!(unpacked.get_layout(), dna.get_layout());
+        for (gene, unpacked_gene) in dna.genes.iter().zip(unpacked.genes.iter()) {
+            let influence_error =
+                (gene.get_influence().clamp(-3.0, 3.0) - unpacked_gene.get_influence()).abs();
+            assert!(influence_error <= 3.0 / 15.0 + 1e-6);
+            for (marker, unpacked_marker) in gene
+                .get_markers()
+                .iter()
+                .zip(unpacked_gene.get_markers().iter())
+            {
+                let error = (marker.clamp(-3.0, 3.0) - unpacked_marker).abs();
+                assert!(error <= 3.0 / 255.0 + 1e-6);
+            }
+        }
+    }
+    #[test]
+    fn values_do_not_straddle_words() {
+        // 256 bits hold 25 values of 10 bits with 6 bits left over
+        let packing = Packing::new(10, 10, 1.0).unwrap();
+        let layout = Layout {
+            pool_size: 2,
+            gene_size: 12,
+        };
+        assert_eq!(packing.word_count(layout), Ok(2));
+        let mut dna = DNA::new(2, 12);
+        for gene in dna.genes.iter_mut() {
+            gene.zero();
+        }
+        dna.genes[1].markers[0].value = 1.0;
+        let words = packing.pack(&dna);
+        // Second gene influence is the 14th value, last 6 bits of first word are padding
+        assert_eq!(words[0][31] & 0x3f, 0);
+        assert_eq!(
+            packing.unpack(&words, layout).unwrap().genes[1].get_influence(),
+            1.0
+        );
+    }
+    #[test]
+    fn abi_layout() {
+        let packing = Packing::new(16, 16, 3.0).unwrap();
+        let dna = DNA::new(1, 1);
+        let abi = packing.to_abi(&dna);
+        assert_eq!(abi[31], 0x20);
+        assert_eq!(abi[63], 1);
+        let restored = packing.from_abi(&abi, dna.get_layout()).unwrap();
+        assert_eq!(packing.to_abi_hex(&restored), packing.to_abi_hex(&dna));
+    }
+    #[test]
+    fn rejects_bad_input() {
+        assert!(Packing::new(0, 8, 3.0).is_err());
+        assert!(Packing::new(8, 17, 3.0).is_err());
+        assert!(Packing::new(8, 8, -1.0).is_err());
+        let packing = Packing::new(8, 8, 3.0).unwrap();
+        let dna = DNA::new(64, 8);
+        let words = packing.pack(&dna);
+        assert_eq!(
+            packing.unpack(&words[1..], dna.get_layout()).err(),
+            Some(Error::LengthMismatch {
+                expected: words.len(),
+                found: words.len() - 1
+            })
+        );
+        let empty = Layout {
+            pool_size: 2,
+            gene_size: 0,
+        };
+        let invalid = Some(Error::InvalidSetting("gene size needs to be more than 0"));
+        assert_eq!(packing.unpack(&[[0; 32]], empty).err(), invalid);
+        assert_eq!(packing.from_abi(&[0; 96], empty).err(), invalid);
+    }
+}
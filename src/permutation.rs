@@ -0,0 +1,589 @@
+mod mutation;
+use crate::error::Error;
+use crate::utils;
+use mutation::MutationType;
+use rand::prelude::*;
+
+/// Crossover operator used when breeding two `Permutation`
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Crossover {
+    /// Partially mapped crossover
+    PMX,
+    /// Order crossover (OX)
+    ORDER,
+    /// Cycle crossover
+    CYCLE,
+}
+
+/// A genome holding an ordering of `0..size`, every operator keeps it a valid permutation
+pub struct Permutation {
+    pub order: Vec<u16>,
+}
+
+#[allow(clippy::inherent_to_string)]
+impl Permutation {
+    /// Create a new shuffled permutation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let permutation = Permutation::new(8);
+    /// ```
+    pub fn new(size: u16) -> Permutation {
+        let mut permutation = Permutation::identity(size);
+        permutation.order.shuffle(&mut thread_rng());
+        permutation
+    }
+    /// Create the identity permutation, `0..size` in order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let permutation = Permutation::identity(4);
+    ///
+    /// assert_eq!(permutation.order, vec![0, 1, 2, 3]);
+    /// ```
+    pub fn identity(size: u16) -> Permutation {
+        Permutation {
+            order: (0..size).collect(),
+        }
+    }
+    /// Get the number of elements in the permutation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let permutation = Permutation::new(8);
+    ///
+    /// assert_eq!(permutation.len(), 8);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+    /// Check if the permutation is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let permutation = Permutation::new(0);
+    ///
+    /// assert!(permutation.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+    /// Check if every element of `0..len` appears exactly once
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let permutation = Permutation::new(8);
+    ///
+    /// assert!(permutation.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        let mut seen = vec![false; self.order.len()];
+        for &value in self.order.iter() {
+            match seen.get_mut(value as usize) {
+                Some(s) if !*s => *s = true,
+                _ => return false,
+            }
+        }
+        true
+    }
+    /// Breed two `Permutation` with the given crossover operator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Crossover, Permutation};
+    ///
+    /// let left = Permutation::new(8);
+    /// let right = Permutation::new(8);
+    ///
+    /// let child = Permutation::merge(&left, &right, Crossover::PMX, false).unwrap();
+    ///
+    /// assert!(child.is_valid());
+    /// ```
+    pub fn merge(
+        left: &Permutation,
+        right: &Permutation,
+        crossover: Crossover,
+        mutate: bool,
+    ) -> Option<Permutation> {
+        let mut child = match crossover {
+            Crossover::PMX => {
+                let (start, end) = cut_points(left.len());
+                Permutation::pmx(left, right, start, end)
+            }
+            Crossover::ORDER => {
+                let (start, end) = cut_points(left.len());
+                Permutation::order_crossover(left, right, start, end)
+            }
+            Crossover::CYCLE => Permutation::cycle_crossover(left, right),
+        }
+        .ok()?;
+        if mutate && thread_rng().gen::<f32>() >= 0.9 {
+            child.mutate();
+        }
+        Some(child)
+    }
+    /// Partially mapped crossover, `left[start..end]` is kept and the rest is mapped from `right`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let left = Permutation::from(vec![0, 1, 2, 3, 4]);
+    /// let right = Permutation::from(vec![4, 3, 2, 1, 0]);
+    ///
+    /// let child = Permutation::pmx(&left, &right, 1, 3).unwrap();
+    ///
+    /// assert_eq!(child.order, vec![4, 1, 2, 3, 0]);
+    /// assert!(Permutation::pmx(&left, &right, 3, 1).is_err());
+    /// ```
+    pub fn pmx(
+        left: &Permutation,
+        right: &Permutation,
+        start: usize,
+        end: usize,
+    ) -> Result<Permutation, Error> {
+        check_parents(left, right)?;
+        check_range(start, end, left.len())?;
+        let size = left.len();
+        let mut child: Vec<Option<u16>> = vec![None; size];
+        let right_position = positions(right);
+        for (c, v) in child[start..end].iter_mut().zip(&left.order[start..end]) {
+            *c = Some(*v);
+        }
+        for i in start..end {
+            let value = right.order[i];
+            if left.order[start..end].contains(&value) {
+                continue;
+            }
+            // Follow the mapping until we land outside of the copied segment
+            let mut position = i;
+            while position >= start && position < end {
+                position = right_position[left.order[position] as usize];
+            }
+            child[position] = Some(value);
+        }
+        Ok(Permutation {
+            order: child
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c.unwrap_or(right.order[i]))
+                .collect(),
+        })
+    }
+    /// Order crossover, `left[start..end]` is kept and the rest is filled in the order of `right`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let left = Permutation::from(vec![0, 1, 2, 3, 4]);
+    /// let right = Permutation::from(vec![4, 3, 2, 1, 0]);
+    ///
+    /// let child = Permutation::order_crossover(&left, &right, 1, 3).unwrap();
+    ///
+    /// assert_eq!(child.order, vec![3, 1, 2, 0, 4]);
+    /// assert!(Permutation::order_crossover(&left, &right, 1, 6).is_err());
+    /// ```
+    pub fn order_crossover(
+        left: &Permutation,
+        right: &Permutation,
+        start: usize,
+        end: usize,
+    ) -> Result<Permutation, Error> {
+        check_parents(left, right)?;
+        check_range(start, end, left.len())?;
+        let size = left.len();
+        let mut order = left.order.clone();
+        let segment = &left.order[start..end];
+        let mut fill = (0..size)
+            .map(|i| right.order[(end + i) % size])
+            .filter(|v| !segment.contains(v));
+        for i in 0..size - (end - start) {
+            // Both parents hold the same elements, so exactly enough are left to fill
+            if let Some(value) = fill.next() {
+                order[(end + i) % size] = value;
+            }
+        }
+        Ok(Permutation { order })
+    }
+    /// Cycle crossover, alternating cycles are taken from `left` and `right`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let left = Permutation::from(vec![0, 1, 2, 3, 4]);
+    /// let right = Permutation::from(vec![1, 0, 3, 4, 2]);
+    ///
+    /// let child = Permutation::cycle_crossover(&left, &right).unwrap();
+    ///
+    /// assert_eq!(child.order, vec![0, 1, 3, 4, 2]);
+    /// assert!(Permutation::cycle_crossover(&left, &Permutation::identity(4)).is_err());
+    /// ```
+    pub fn cycle_crossover(left: &Permutation, right: &Permutation) -> Result<Permutation, Error> {
+        check_parents(left, right)?;
+        let size = left.len();
+        let mut order = vec![0; size];
+        let mut visited = vec![false; size];
+        let left_position = positions(left);
+        let mut from_left = true;
+        for i in 0..size {
+            if visited[i] {
+                continue;
+            }
+            let mut position = i;
+            while !visited[position] {
+                visited[position] = true;
+                order[position] = if from_left {
+                    left.order[position]
+                } else {
+                    right.order[position]
+                };
+                position = left_position[right.order[position] as usize];
+            }
+            from_left = !from_left;
+        }
+        Ok(Permutation { order })
+    }
+    /// Swap the elements at two positions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let mut permutation = Permutation::identity(4);
+    ///
+    /// permutation.swap(0, 3).unwrap();
+    ///
+    /// assert_eq!(permutation.order, vec![3, 1, 2, 0]);
+    /// assert!(permutation.swap(0, 4).is_err());
+    /// ```
+    pub fn swap(&mut self, first: usize, second: usize) -> Result<(), Error> {
+        check_position(first, self.len())?;
+        check_position(second, self.len())?;
+        self.order.swap(first, second);
+        Ok(())
+    }
+    /// Move the element at `from` to `to`, shifting the ones in between
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let mut permutation = Permutation::identity(4);
+    ///
+    /// permutation.insert(0, 2).unwrap();
+    ///
+    /// assert_eq!(permutation.order, vec![1, 2, 0, 3]);
+    /// assert!(permutation.insert(4, 0).is_err());
+    /// ```
+    pub fn insert(&mut self, from: usize, to: usize) -> Result<(), Error> {
+        check_position(from, self.len())?;
+        check_position(to, self.len())?;
+        let value = self.order.remove(from);
+        self.order.insert(to, value);
+        Ok(())
+    }
+    /// Shuffle the elements in `start..end`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let mut permutation = Permutation::identity(8);
+    ///
+    /// permutation.scramble(2, 6).unwrap();
+    ///
+    /// assert!(permutation.is_valid());
+    /// assert!(permutation.scramble(6, 2).is_err());
+    /// ```
+    pub fn scramble(&mut self, start: usize, end: usize) -> Result<(), Error> {
+        check_range(start, end, self.len())?;
+        self.order[start..end].shuffle(&mut thread_rng());
+        Ok(())
+    }
+    /// Reverse the elements in `start..end`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let mut permutation = Permutation::identity(4);
+    ///
+    /// permutation.inversion(1, 4).unwrap();
+    ///
+    /// assert_eq!(permutation.order, vec![0, 3, 2, 1]);
+    /// assert!(permutation.inversion(1, 5).is_err());
+    /// ```
+    pub fn inversion(&mut self, start: usize, end: usize) -> Result<(), Error> {
+        check_range(start, end, self.len())?;
+        self.order[start..end].reverse();
+        Ok(())
+    }
+    /// Mutate the permutation, with 4 different types of mutation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let mut permutation = Permutation::new(8);
+    ///
+    /// permutation.mutate();
+    ///
+    /// assert!(permutation.is_valid());
+    /// ```
+    pub fn mutate(&mut self) {
+        if self.order.len() < 2 {
+            return;
+        }
+        let mut rng = thread_rng();
+        // Positions and cut points are drawn inside the permutation, no checks needed
+        match mutation::get_mutation_type() {
+            MutationType::SWAP => {
+                let first = rng.gen_range(0, self.order.len());
+                let second = rng.gen_range(0, self.order.len());
+                self.order.swap(first, second);
+            }
+            MutationType::INSERT => {
+                let from = rng.gen_range(0, self.order.len());
+                let to = rng.gen_range(0, self.order.len());
+                let value = self.order.remove(from);
+                self.order.insert(to, value);
+            }
+            MutationType::SCRAMBLE => {
+                let (start, end) = cut_points(self.order.len());
+                self.order[start..end].shuffle(&mut rng);
+            }
+            MutationType::INVERSION => {
+                let (start, end) = cut_points(self.order.len());
+                self.order[start..end].reverse();
+            }
+        }
+    }
+    /// Convert permutation to string, each element is a 4 char hex
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::Permutation;
+    ///
+    /// let permutation = Permutation::identity(2);
+    ///
+    /// assert_eq!(permutation.to_string(), "00000001");
+    /// ```
+    pub fn to_string(&self) -> String {
+        self.order
+            .iter()
+            .map(|v| utils::u16_to_string(*v))
+            .collect::<String>()
+    }
+}
+
+/// Pick two random cut points with `start < end`
+fn cut_points(size: usize) -> (usize, usize) {
+    if size < 2 {
+        return (0, size);
+    }
+    let mut rng = thread_rng();
+    let start = rng.gen_range(0, size - 1);
+    let end = rng.gen_range(start + 1, size + 1);
+    (start, end)
+}
+
+/// Check both parents are valid permutations of the same elements
+fn check_parents(left: &Permutation, right: &Permutation) -> Result<(), Error> {
+    if left.len() != right.len() {
+        return Err(Error::LengthMismatch {
+            expected: left.len(),
+            found: right.len(),
+        });
+    }
+    match left.is_valid() && right.is_valid() {
+        true => Ok(()),
+        false => Err(Error::InvalidSetting(
+            "parents need to be valid permutations",
+        )),
+    }
+}
+
+/// Check `start..end` is a range within a permutation of `size`
+fn check_range(start: usize, end: usize, size: usize) -> Result<(), Error> {
+    match start <= end && end <= size {
+        true => Ok(()),
+        false => Err(Error::InvalidSetting(
+            "range needs to be within the permutation",
+        )),
+    }
+}
+
+/// Check `position` is within a permutation of `size`
+fn check_position(position: usize, size: usize) -> Result<(), Error> {
+    match position < size {
+        true => Ok(()),
+        false => Err(Error::InvalidSetting(
+            "position needs to be within the permutation",
+        )),
+    }
+}
+
+/// Lookup table from element to its position
+fn positions(permutation: &Permutation) -> Vec<usize> {
+    let mut position = vec![0; permutation.len()];
+    for (i, v) in permutation.order.iter().enumerate() {
+        position[*v as usize] = i;
+    }
+    position
+}
+
+/// Convert a vector into permutation
+///
+/// # Examples
+///
+/// ```
+/// use genome::Permutation;
+///
+/// let permutation = Permutation::from(vec![2, 0, 1]);
+/// ```
+impl std::convert::From<Vec<u16>> for Permutation {
+    fn from(order: Vec<u16>) -> Permutation {
+        Permutation { order }
+    }
+}
+
+/// Convert permutation to string
+///
+/// # Examples
+///
+/// ```
+/// use genome::Permutation;
+///
+/// let permutation = Permutation::new(4);
+///
+/// let permutation_str = String::from(permutation);
+/// ```
+impl std::convert::From<Permutation> for String {
+    fn from(permutation: Permutation) -> String {
+        permutation.to_string()
+    }
+}
+
+/// Convert string to permutation
+///
+/// # Examples
+///
+/// ```
+/// use genome::Permutation;
+///
+/// let permutation = Permutation::new(4);
+/// let permutation_str = permutation.to_string();
+///
+/// let permutation_copy = Permutation::from(permutation_str);
+/// ```
+impl std::convert::From<String> for Permutation {
+    fn from(permutation: String) -> Permutation {
+        Permutation {
+            order: utils::partition_string(&permutation, 4)
+                .iter()
+                .map(|v| utils::u16_from_str(v))
+                .collect(),
+        }
+    }
+}
+
+impl PartialEq for Permutation {
+    fn eq(&self, other: &Self) -> bool {
+        self.order == other.order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn can_be_converted_and_back() {
+        let permutation = Permutation::new(16);
+        let string_value = permutation.to_string();
+        let restored = Permutation::from(string_value);
+        assert!(permutation == restored);
+    }
+    #[test]
+    fn should_detect_invalid() {
+        assert!(!Permutation::from(vec![0, 0, 1]).is_valid());
+        assert!(!Permutation::from(vec![0, 3, 1]).is_valid());
+        assert!(Permutation::from(vec![2, 0, 1]).is_valid());
+    }
+    #[test]
+    fn pmx_follows_mapping() {
+        let left = Permutation::from(vec![8, 4, 7, 3, 6, 2, 5, 1, 9, 0]);
+        let right = Permutation::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let child = Permutation::pmx(&left, &right, 3, 8).unwrap();
+        assert_eq!(child.order, vec![0, 7, 4, 3, 6, 2, 5, 1, 8, 9]);
+    }
+    #[test]
+    fn order_crossover_wraps_around() {
+        let left = Permutation::from(vec![8, 4, 7, 3, 6, 2, 5, 1, 9, 0]);
+        let right = Permutation::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let child = Permutation::order_crossover(&left, &right, 3, 8).unwrap();
+        assert_eq!(child.order, vec![0, 4, 7, 3, 6, 2, 5, 1, 8, 9]);
+    }
+    #[test]
+    fn crossovers_stay_valid() {
+        for crossover in [Crossover::PMX, Crossover::ORDER, Crossover::CYCLE].iter() {
+            for _ in 0..64 {
+                let left = Permutation::new(12);
+                let right = Permutation::new(12);
+                let child = Permutation::merge(&left, &right, *crossover, true).unwrap();
+                assert!(child.is_valid());
+                assert_eq!(child.len(), 12);
+            }
+        }
+    }
+    #[test]
+    fn cannot_be_merged() {
+        let left = Permutation::new(4);
+        let right = Permutation::new(5);
+        assert!(Permutation::merge(&left, &right, Crossover::PMX, false).is_none());
+        let invalid = Permutation::from(vec![0, 0, 1, 2]);
+        assert!(Permutation::merge(&left, &invalid, Crossover::CYCLE, false).is_none());
+        assert_eq!(
+            Permutation::pmx(&left, &right, 0, 2).err(),
+            Some(Error::LengthMismatch {
+                expected: 4,
+                found: 5
+            })
+        );
+    }
+    #[test]
+    fn mutations_stay_valid() {
+        let mut permutation = Permutation::new(10);
+        for _ in 0..256 {
+            permutation.mutate();
+            assert!(permutation.is_valid());
+        }
+    }
+}
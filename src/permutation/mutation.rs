@@ -0,0 +1,19 @@
+use rand::prelude::*;
+
+#[allow(clippy::upper_case_acronyms)]
+pub enum MutationType {
+    SWAP,
+    INSERT,
+    SCRAMBLE,
+    INVERSION,
+}
+
+pub fn get_mutation_type() -> MutationType {
+    match thread_rng().gen_range(0, 4) {
+        0 => MutationType::SWAP,
+        1 => MutationType::INSERT,
+        2 => MutationType::SCRAMBLE,
+        3 => MutationType::INVERSION,
+        _ => panic!("Not suppose to reach here"),
+    }
+}
@@ -0,0 +1,317 @@
+use crate::dna::DNA;
+use crate::error::Error;
+
+/// GF(256) with the polynomial x^8 + x^4 + x^3 + x^2 + 1 and generator 2
+const EXP: [u8; 512] = exp_table();
+const LOG: [u8; 256] = log_table();
+
+/// Protects the binary DNA with Reed-Solomon parity, written as a hex string.
+///
+/// The bytes are split in blocks of up to 255 symbols, each block ends with `2 * max_errors`
+/// parity bytes and can correct up to `max_errors` wrong bytes. A wrong byte is one or both of
+/// its hex chars, chars that are not hex are read as `0` and corrected like any other error.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ReedSolomon {
+    parity: usize,
+}
+
+/// A byte that was fixed while decoding
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Correction {
+    /// Position of the first hex char of the byte in the string
+    pub position: usize,
+    /// The byte as it was read
+    pub found: u8,
+    /// The byte after correction
+    pub corrected: u8,
+}
+
+impl ReedSolomon {
+    /// Create a new encoding that corrects up to `max_errors` bytes in every block
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::ReedSolomon;
+    ///
+    /// let reed_solomon = ReedSolomon::new(4).unwrap();
+    /// ```
+    pub fn new(max_errors: u8) -> Result<ReedSolomon, Error> {
+        if max_errors == 0 || max_errors > 126 {
+            return Err(Error::InvalidSetting(
+                "errors to correct need to be between 1 and 126",
+            ));
+        }
+        Ok(ReedSolomon {
+            parity: max_errors as usize * 2,
+        })
+    }
+    /// Convert DNA to a protected hex string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ReedSolomon, DNA};
+    ///
+    /// let reed_solomon = ReedSolomon::new(4).unwrap();
+    /// let dna = DNA::new(2, 2);
+    ///
+    /// let dna_str = reed_solomon.encode(&dna);
+    /// ```
+    pub fn encode(&self, dna: &DNA) -> String {
+        dna.to_bytes()
+            .chunks(255 - self.parity)
+            .flat_map(|block| [block, &self.parity_of(block)[..]].concat())
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+    /// Convert a protected hex string to DNA, together with the bytes that were corrected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{ReedSolomon, DNA};
+    ///
+    /// let reed_solomon = ReedSolomon::new(4).unwrap();
+    /// let dna = DNA::new(2, 2);
+    /// let mut dna_str = reed_solomon.encode(&dna);
+    /// // The first byte is the pool size, "02", misread as "0O"
+    /// dna_str.replace_range(1..2, "O");
+    ///
+    /// let (dna_copy, corrections) = reed_solomon.decode(&dna_str).unwrap();
+    ///
+    /// assert_eq!(dna_copy.to_string(), dna.to_string());
+    /// assert_eq!(corrections[0].position, 0);
+    /// ```
+    pub fn decode(&self, dna_str: &str) -> Result<(DNA, Vec<Correction>), Error> {
+        let chars = dna_str.chars().collect::<Vec<char>>();
+        if chars.len() % 2 != 0 {
+            return Err(Error::Malformed("hex string needs an even length"));
+        }
+        let mut codeword = chars
+            .chunks(2)
+            .map(|pair| {
+                pair.iter()
+                    .fold(0, |b, c| b << 4 | c.to_digit(16).unwrap_or(0) as u8)
+            })
+            .collect::<Vec<u8>>();
+        if codeword.len() % 255 != 0 && codeword.len() % 255 <= self.parity {
+            return Err(Error::Malformed("last block is too short"));
+        }
+        let mut corrections = Vec::new();
+        let mut data = Vec::new();
+        for (i, block) in codeword.chunks_mut(255).enumerate() {
+            for (k, corrected) in self.correct(block)? {
+                corrections.push(Correction {
+                    position: (i * 255 + k) * 2,
+                    found: block[k],
+                    corrected,
+                });
+                block[k] = corrected;
+            }
+            data.extend_from_slice(&block[..block.len() - self.parity]);
+        }
+        Ok((DNA::from_bytes(&data)?, corrections))
+    }
+    /// Get the parity bytes of a block, the remainder of `block * x^parity` by the generator
+    fn parity_of(&self, block: &[u8]) -> Vec<u8> {
+        let generator = (0..self.parity).fold(vec![1], |g, i| mul_poly(&g, &[1, EXP[i]]));
+        let mut remainder = vec![0; self.parity];
+        for byte in block.iter() {
+            let factor = byte ^ remainder[0];
+            remainder.remove(0);
+            remainder.push(0);
+            for (r, g) in remainder.iter_mut().zip(generator[1..].iter()) {
+                *r ^= mul(factor, *g);
+            }
+        }
+        remainder
+    }
+    /// Find the wrong bytes of a block, return their index and corrected value
+    fn correct(&self, block: &[u8]) -> Result<Vec<(usize, u8)>, Error> {
+        let syndrome = syndromes(block, self.parity);
+        if syndrome.iter().all(|s| *s == 0) {
+            return Ok(Vec::new());
+        }
+        // Berlekamp-Massey, polynomials are stored lowest degree first
+        let mut locator = vec![1];
+        let mut previous = vec![1];
+        let mut errors = 0;
+        let mut shift = 1;
+        let mut previous_discrepancy = 1;
+        for n in 0..self.parity {
+            let discrepancy = (1..=errors).fold(syndrome[n], |d, i| {
+                d ^ mul(*locator.get(i).unwrap_or(&0), syndrome[n - i])
+            });
+            if discrepancy == 0 {
+                shift += 1;
+                continue;
+            }
+            let factor = div(discrepancy, previous_discrepancy);
+            let mut next = locator.clone();
+            next.resize(next.len().max(previous.len() + shift), 0);
+            for (i, p) in previous.iter().enumerate() {
+                next[i + shift] ^= mul(factor, *p);
+            }
+            if 2 * errors <= n {
+                previous = locator;
+                errors = n + 1 - errors;
+                previous_discrepancy = discrepancy;
+                shift = 1;
+            } else {
+                shift += 1;
+            }
+            locator = next;
+        }
+        if 2 * errors > self.parity {
+            return Err(Error::Uncorrectable);
+        }
+        // Forney, the error value at locator X is X * omega(1/X) / locator'(1/X)
+        let mut omega = mul_poly(&syndrome, &locator);
+        omega.truncate(self.parity);
+        let n = block.len();
+        let fixes = (0..n)
+            .filter_map(|k| {
+                let power = (n - 1 - k) % 255;
+                let inverse = EXP[(255 - power) % 255];
+                if eval(&locator, inverse) != 0 {
+                    return None;
+                }
+                let derivative = locator
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .step_by(2)
+                    .fold(0, |d, (i, l)| d ^ mul(*l, pow(inverse, i - 1)));
+                let value = mul(EXP[power], div(eval(&omega, inverse), derivative));
+                Some((k, block[k] ^ value))
+            })
+            .collect::<Vec<(usize, u8)>>();
+        if fixes.len() != errors {
+            return Err(Error::Uncorrectable);
+        }
+        let mut fixed = block.to_vec();
+        for (k, value) in fixes.iter() {
+            fixed[*k] = *value;
+        }
+        if syndromes(&fixed, self.parity).iter().any(|s| *s != 0) {
+            return Err(Error::Uncorrectable);
+        }
+        Ok(fixes)
+    }
+}
+
+/// Evaluate the block at `2^i` for every parity byte, all zero for a valid block
+fn syndromes(block: &[u8], parity: usize) -> Vec<u8> {
+    (0..parity)
+        .map(|i| block.iter().fold(0, |s, b| mul(s, EXP[i]) ^ b))
+        .collect()
+}
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP[LOG[a as usize] as usize + LOG[b as usize] as usize]
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    EXP[LOG[a as usize] as usize + 255 - LOG[b as usize] as usize]
+}
+
+fn pow(a: u8, power: usize) -> u8 {
+    (0..power).fold(1, |p, _| mul(p, a))
+}
+
+fn mul_poly(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] ^= mul(*x, *y);
+        }
+    }
+    product
+}
+
+/// Evaluate a polynomial stored lowest degree first
+fn eval(poly: &[u8], x: u8) -> u8 {
+    poly.iter().rev().fold(0, |v, c| mul(v, x) ^ c)
+}
+
+const fn exp_table() -> [u8; 512] {
+    let mut table = [0; 512];
+    let mut value: u16 = 1;
+    let mut i = 0;
+    while i < 512 {
+        table[i] = value as u8;
+        value <<= 1;
+        if value & 0x100 != 0 {
+            value ^= 0x11d;
+        }
+        i += 1;
+    }
+    table
+}
+
+const fn log_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 255 {
+        table[EXP[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    #[test]
+    fn clean_string_has_no_corrections() {
+        let reed_solomon = ReedSolomon::new(3).unwrap();
+        let dna = DNA::new(4, 4);
+        let (dna_copy, corrections) = reed_solomon.decode(&reed_solomon.encode(&dna)).unwrap();
+        assert_eq!(dna_copy.to_string(), dna.to_string());
+        assert!(corrections.is_empty());
+    }
+    #[test]
+    fn corrects_up_to_max_errors() {
+        let mut rng = thread_rng();
+        let reed_solomon = ReedSolomon::new(4).unwrap();
+        // 8 * 9 * 4 bytes of markers, spread over two blocks
+        let dna = DNA::new(8, 8);
+        let dna_str = reed_solomon.encode(&dna);
+        for _ in 0..32 {
+            let mut chars = dna_str.chars().collect::<Vec<char>>();
+            let block = rng.gen_range(0, 2) * 255;
+            let length = (chars.len() / 2 - block).min(255);
+            let mut positions = (block..block + length).choose_multiple(&mut rng, 4);
+            positions.sort();
+            for p in positions.iter() {
+                chars[p * 2] = if chars[p * 2] == 'f' { 'e' } else { 'f' };
+            }
+            let typo = chars.iter().collect::<String>();
+            let (dna_copy, corrections) = reed_solomon.decode(&typo).unwrap();
+            assert_eq!(dna_copy.to_string(), dna.to_string());
+            assert_eq!(
+                corrections
+                    .iter()
+                    .map(|c| c.position)
+                    .collect::<Vec<usize>>(),
+                positions.iter().map(|p| p * 2).collect::<Vec<usize>>()
+            );
+        }
+    }
+    #[test]
+    fn too_many_errors_are_reported() {
+        let reed_solomon = ReedSolomon::new(1).unwrap();
+        let mut dna_str = reed_solomon.encode(&DNA::new(2, 2));
+        dna_str.replace_range(0..4, "zzzz");
+        assert!(reed_solomon.decode(&dna_str).is_err());
+        assert!(ReedSolomon::new(0).is_err());
+    }
+}
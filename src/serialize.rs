@@ -0,0 +1,244 @@
+//! Serde support, enabled with the `serde` feature.
+//!
+//! `DNA` serializes to its canonical string by default, the same as `DNA::to_string`. The
+//! structured form with pool size, gene size and the markers of each gene can be selected
+//! with `#[serde(with = "genome::serialize::structured")]`. Both forms are validated on
+//! deserialize.
+use crate::condition::Condition;
+use crate::dna::DNA;
+use crate::encoding::Encoding;
+use crate::gene::Gene;
+use crate::model::Model;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+impl Serialize for DNA {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        compact::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DNA {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DNA, D::Error> {
+        compact::deserialize(deserializer)
+    }
+}
+
+/// The influence and markers of a gene
+#[derive(Serialize, Deserialize)]
+struct StructuredGene {
+    influence: f32,
+    markers: Vec<f32>,
+}
+
+impl Serialize for Gene {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StructuredGene {
+            influence: self.get_influence(),
+            markers: self.get_markers(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Gene {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Gene, D::Error> {
+        let gene = StructuredGene::deserialize(deserializer)?;
+        if gene.markers.is_empty() {
+            return Err(de::Error::custom("Markers needs to be more than 0"));
+        }
+        Ok(Gene::from([vec![gene.influence], gene.markers].concat()))
+    }
+}
+
+/// The class and number of classes of a condition
+#[derive(Serialize, Deserialize)]
+struct StructuredCondition {
+    num_classes: u16,
+    class_id: u16,
+}
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StructuredCondition {
+            num_classes: self.num_classes,
+            class_id: self.class_id,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Condition, D::Error> {
+        let condition = StructuredCondition::deserialize(deserializer)?;
+        if condition.class_id >= condition.num_classes {
+            return Err(de::Error::custom(
+                "Class needs to be less than the number of classes",
+            ));
+        }
+        Ok(Condition {
+            num_classes: condition.num_classes,
+            class_id: condition.class_id,
+        })
+    }
+}
+
+/// Serialize `DNA` as its canonical string, checked with `DNA::is_valid` on deserialize
+pub mod compact {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(dna: &DNA, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&dna.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DNA, D::Error> {
+        let dna_str = String::deserialize(deserializer)?;
+        let dna = dna_str.parse::<DNA>().map_err(de::Error::custom)?;
+        if !DNA::is_valid(dna_str) {
+            return Err(de::Error::custom("checksum mismatch"));
+        }
+        Ok(dna)
+    }
+}
+
+/// Serialize `DNA` as pool size, gene size and the influence and markers of each gene
+pub mod structured {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct StructuredDNARef<'a> {
+        pool_size: u16,
+        gene_size: u16,
+        #[serde(skip_serializing_if = "is_default")]
+        encoding: Encoding,
+        genes: &'a [Gene],
+        #[serde(skip_serializing_if = "<[Condition]>::is_empty")]
+        conditions: &'a [Condition],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        model: &'a Option<Model>,
+    }
+
+    #[derive(Deserialize)]
+    struct StructuredDNA {
+        pool_size: u16,
+        gene_size: u16,
+        #[serde(default)]
+        encoding: Encoding,
+        genes: Vec<Gene>,
+        #[serde(default)]
+        conditions: Vec<Condition>,
+        #[serde(default)]
+        model: Option<Model>,
+    }
+
+    fn is_default(encoding: &Encoding) -> bool {
+        *encoding == Encoding::default()
+    }
+
+    pub fn serialize<S: Serializer>(dna: &DNA, serializer: S) -> Result<S::Ok, S::Error> {
+        StructuredDNARef {
+            pool_size: dna.pool_size,
+            gene_size: dna.gene_size,
+            encoding: dna.get_encoding(),
+            genes: &dna.genes,
+            conditions: dna.get_conditions(),
+            model: &dna.model,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DNA, D::Error> {
+        let structured = StructuredDNA::deserialize(deserializer)?;
+        if structured.gene_size == 0 {
+            return Err(de::Error::custom("gene size needs to be more than 0"));
+        }
+        if structured.genes.len() != structured.pool_size as usize {
+            return Err(de::Error::invalid_length(
+                structured.genes.len(),
+                &"pool_size genes",
+            ));
+        }
+        if let Some(gene) = structured
+            .genes
+            .iter()
+            .find(|g| g.markers.len() != structured.gene_size as usize + 1)
+        {
+            return Err(de::Error::invalid_length(
+                gene.markers.len() - 1,
+                &"gene_size markers",
+            ));
+        }
+        let mut dna = DNA::from_genes(structured.gene_size, structured.genes);
+        dna.set_encoding(structured.encoding)
+            .map_err(de::Error::custom)?;
+        dna.set_conditions(structured.conditions)
+            .map_err(de::Error::custom)?;
+        dna.model = structured.model;
+        Ok(dna)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[derive(Serialize, Deserialize)]
+    struct Asset {
+        #[serde(with = "structured")]
+        dna: DNA,
+    }
+    #[test]
+    fn compact_round_trip() {
+        let dna = DNA::new_conditional(4, 2, &[3]);
+        let json = serde_json::to_string(&dna).unwrap();
+        assert_eq!(json, format!("\"{}\"", dna.to_string()));
+        let restored: DNA = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.to_string(), dna.to_string());
+    }
+    #[test]
+    fn compact_rejects_checksum_mismatch() {
+        let dna = DNA::new(4, 2);
+        let mut dna_str = dna.to_string();
+        dna_str.replace_range(0..8, "00000000");
+        assert!(serde_json::from_str::<DNA>(&format!("\"{}\"", dna_str)).is_err());
+    }
+    #[test]
+    fn structured_round_trip() {
+        let mut dna = DNA::new_conditional(4, 2, &[3]);
+        dna.set_encoding(Encoding::U8(3.0)).unwrap();
+        dna.model = Some(Model::new("faces-v2", dna.get_layout()).unwrap());
+        let json = serde_json::to_string(&Asset { dna: dna.clone() }).unwrap();
+        let restored: Asset = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.dna.to_string(), dna.to_string());
+        assert_eq!(restored.dna.to_latent_vec(), dna.to_latent_vec());
+        assert_eq!(restored.dna.model, dna.model);
+    }
+    #[test]
+    fn structured_is_validated() {
+        let json =
+            r#"{"dna":{"pool_size":2,"gene_size":1,"genes":[{"influence":0.5,"markers":[1.0]}]}}"#;
+        assert!(serde_json::from_str::<Asset>(json).is_err());
+        let json =
+            r#"{"dna":{"pool_size":1,"gene_size":2,"genes":[{"influence":0.5,"markers":[1.0]}]}}"#;
+        assert!(serde_json::from_str::<Asset>(json).is_err());
+        let json = r#"{"dna":{"pool_size":1,"gene_size":1,"genes":[{"influence":0.5,"markers":[1.0]}],"conditions":[{"num_classes":2,"class_id":2}]}}"#;
+        assert!(serde_json::from_str::<Asset>(json).is_err());
+        let json =
+            r#"{"dna":{"pool_size":1,"gene_size":1,"genes":[{"influence":0.5,"markers":[1.0]}]}}"#;
+        let asset = serde_json::from_str::<Asset>(json).unwrap();
+        assert_eq!(asset.dna.to_latent_vec(), vec![0.5, 1.0]);
+        let json = format!(
+            r#"{{"id":"{}","layout_hash":[0,0,0,0,0,0,0,0]}}"#,
+            "x".repeat(256)
+        );
+        assert!(serde_json::from_str::<Model>(&json).is_err());
+    }
+    #[test]
+    fn gene_is_structured() {
+        let gene = Gene::from(vec![0.5, 1.0, -1.0]);
+        let json = serde_json::to_string(&gene).unwrap();
+        assert_eq!(json, r#"{"influence":0.5,"markers":[1.0,-1.0]}"#);
+        let restored: Gene = serde_json::from_str(&json).unwrap();
+        assert!(restored == gene);
+    }
+}
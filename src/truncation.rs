@@ -0,0 +1,91 @@
+use crate::dna::DNA;
+use crate::error::Error;
+
+/// Latent transforms for nicer samples, applied to the markers in order: truncation toward
+/// `mean`, clipping to `sigma` and normalization to the hypersphere. Influences are left as is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Truncation {
+    // Pull toward the mean, 1 leaves markers as they are and 0 moves them onto the mean
+    pub psi: f32,
+    // The mean of the markers only latent, the zero mean of the prior when `None`
+    pub mean: Option<Vec<f32>>,
+    // Clip every marker to `-sigma..=sigma`
+    pub sigma: Option<f32>,
+    // Scale the markers to the norm expected of a standard normal latent
+    pub normalize: bool,
+}
+
+impl Default for Truncation {
+    fn default() -> Truncation {
+        Truncation {
+            psi: 1.0,
+            mean: None,
+            sigma: None,
+            normalize: false,
+        }
+    }
+}
+
+impl Truncation {
+    /// Apply the transforms to a DNA
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genome::{Truncation, DNA};
+    ///
+    /// let mut dna = DNA::new(2, 2);
+    /// let truncation = Truncation {
+    ///     psi: 0.7,
+    ///     sigma: Some(2.0),
+    ///     ..Truncation::default()
+    /// };
+    ///
+    /// truncation.apply(&mut dna).unwrap();
+    /// ```
+    pub fn apply(&self, dna: &mut DNA) -> Result<(), Error> {
+        if self.psi != 1.0 {
+            dna.truncate(self.mean.as_deref(), self.psi)?;
+        }
+        if let Some(sigma) = self.sigma {
+            dna.clip(sigma)?;
+        }
+        if self.normalize {
+            dna.normalize();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dna::LatentMode;
+    #[test]
+    fn default_changes_nothing() {
+        let mut dna = DNA::new(4, 4);
+        let dna_str = dna.to_string();
+        Truncation::default().apply(&mut dna).unwrap();
+        assert_eq!(dna.to_string(), dna_str);
+    }
+    #[test]
+    fn transforms_apply_in_order() {
+        let mut dna = DNA::new(4, 4);
+        let truncation = Truncation {
+            psi: 0.5,
+            mean: Some(vec![1.0; 16]),
+            sigma: Some(0.5),
+            normalize: true,
+        };
+        truncation.apply(&mut dna).unwrap();
+        let latent = dna.to_latent_vec_with(LatentMode::Markers);
+        let norm = latent.iter().map(|m| m * m).sum::<f32>().sqrt();
+        assert!((norm - 4.0).abs() < 1e-4);
+        let truncation = Truncation {
+            mean: Some(vec![1.0; 3]),
+            psi: 0.5,
+            ..Truncation::default()
+        };
+        assert!(truncation.apply(&mut dna).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Uniform values from `next_u32` and `next_u64`, the same on every platform and crate version.
+//!
+//! `rand` changed how `gen`, `gen_range` and `shuffle` turn random words into values between
+//! 0.7 and 0.8, so seeded code draws through these instead to keep its results.
+use rand::RngCore;
+
+/// Draw a float in `0..1` from the top 24 bits of a `u32`
+pub fn uniform_f32<R: RngCore + ?Sized>(rng: &mut R) -> f32 {
+    (rng.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Draw an integer in `low..high` without bias, rejecting the `u64` that would wrap unevenly
+pub fn range<R: RngCore + ?Sized>(rng: &mut R, low: usize, high: usize) -> usize {
+    if low >= high {
+        panic!("Range needs to be non-empty");
+    }
+    let span = (high - low) as u64;
+    // The largest multiple of span, values at or above it are drawn again
+    let zone = u64::MAX - (u64::MAX - span + 1) % span;
+    loop {
+        let value = rng.next_u64();
+        if value <= zone {
+            return low + (value % span) as usize;
+        }
+    }
+}
+
+/// Shuffle `values` in place with Fisher-Yates, from the last element down
+pub fn shuffle<T, R: RngCore + ?Sized>(rng: &mut R, values: &mut [T]) {
+    for i in (1..values.len()).rev() {
+        values.swap(i, range(rng, 0, i + 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    #[test]
+    fn values_are_in_range() {
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            let value = uniform_f32(&mut rng);
+            assert!((0.0..1.0).contains(&value));
+            counts[range(&mut rng, 2, 5) - 2] += 1;
+        }
+        assert!(counts.iter().all(|c| *c > 900 && *c < 1100));
+        assert_eq!(range(&mut rng, 7, 8), 7);
+        assert!(range(&mut rng, 0, usize::MAX) < usize::MAX);
+    }
+    #[test]
+    fn shuffle_is_stable() {
+        // Golden vector, changing this changes every seeded merge
+        let mut values = [0, 1, 2, 3, 4, 5];
+        shuffle(&mut ChaCha20Rng::seed_from_u64(7), &mut values);
+        let mut sorted = values;
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(values, [1, 5, 4, 2, 0, 3]);
+    }
+}
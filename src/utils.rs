@@ -1,3 +1,4 @@
+use crate::error::Error;
 use arrayvec::ArrayVec;
 use std::str;
 
@@ -46,3 +47,103 @@ pub fn f32_from_str<'a>(data: &'a str) -> f32 {
     let decoded_bytes_array: [u8; 4] = decoded_bytes.into_inner().unwrap();
     f32::from_be_bytes(decoded_bytes_array)
 }
+
+pub fn u8_to_string(data: u8) -> String {
+    format!("{:0>2x}", data)
+}
+
+pub fn u8_from_str(data: &str) -> u8 {
+    u8::from_str_radix(data, 16).unwrap()
+}
+
+pub fn bytes_to_string(data: &[u8]) -> String {
+    data.iter().map(|val| format!("{:0>2x}", val)).collect()
+}
+
+pub fn bytes_from_str(data: &str) -> Vec<u8> {
+    partition_str(data, 2)
+        .iter()
+        .map(|c| u8::from_str_radix(c, 16).unwrap())
+        .collect()
+}
+
+pub fn u32_to_string(data: u32) -> String {
+    data.to_be_bytes()
+        .iter()
+        .map(|val| format!("{:0>2x}", val))
+        .collect()
+}
+
+pub fn u32_from_str(data: &str) -> u32 {
+    u32::from_str_radix(data, 16).unwrap()
+}
+
+pub fn write_varint(data: &mut Vec<u8>, value: u64) {
+    let mut value = value;
+    while value >= 0x80 {
+        data.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+/// Read a LEB128 varint, return the value with the number of bytes consumed
+pub fn read_varint(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// CRC-32 with the IEEE polynomial, the one used by zip and png
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data.iter() {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Reads varints and byte strings from the front of a slice
+pub struct Reader<'a> {
+    pub data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < length {
+            return Err(Error::Malformed("unexpected end of data"));
+        }
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(bytes)
+    }
+    pub fn read_varint(&mut self) -> Result<u64, Error> {
+        let (value, length) = read_varint(self.data).ok_or(Error::Malformed("invalid varint"))?;
+        self.data = &self.data[length..];
+        Ok(value)
+    }
+    /// Read a varint length followed by that many bytes
+    pub fn read_prefixed(&mut self) -> Result<&'a [u8], Error> {
+        let length = self.read_varint()?;
+        self.read_bytes(length as usize)
+    }
+    pub fn read_u16(&mut self) -> Result<u16, Error> {
+        let value = self.read_varint()?;
+        if value > u16::MAX as u64 {
+            return Err(Error::Malformed("size does not fit in 16 bits"));
+        }
+        Ok(value as u16)
+    }
+}